@@ -6,9 +6,33 @@
 //!    under standard single-threaded model.
 //!
 //! Following features are provided:
-//!  - [Mutex] and [RwLock] (using [parking_lot](https://docs.rs/parking_lot/latest/parking_lot/) on native platforms
-//!    and [std::cell::RefCell] in WASM).
-//!  - asynchronous [spawn] (not requiring [Send] in WASM) and [sleep](time::sleep),
+//!  - [Mutex] and [RwLock] with mapped and upgradable guards (using [parking_lot](https://docs.rs/parking_lot/latest/parking_lot/) on native platforms
+//!    and [std::cell::RefCell] in WASM). In debug builds re-entrant locking panics
+//!    with locations of both the outstanding guard and the contender on all targets.
+//!    On native platforms `deadlock_detection` feature also enables parking_lot's
+//!    detection of deadlocks between threads.
+//!    Their common API is captured by [MutexApi] and [RwLockApi] traits implemented on all targets.
+//!  - [AsyncMutex] and [AsyncRwLock] whose guards can be held across `.await` points
+//!    (using [tokio](https://docs.rs/tokio/latest/tokio/sync/index.html) on native platforms
+//!    and a FIFO waker queue in WASM), with timed acquisition through [AsyncMutexExt]
+//!    and [AsyncRwLockExt].
+//!  - [Semaphore](sync::Semaphore) with owned permits for limiting concurrency.
+//!  - reusable [Notify](sync::Notify) for signalling repeated events.
+//!  - [Barrier](sync::Barrier), [CountDownLatch](sync::CountDownLatch)
+//!    and [WaitGroup](sync::WaitGroup) for coordinating tasks.
+//!  - hierarchical [CancellationToken](sync::CancellationToken) for graceful shutdown.
+//!  - bounded and unbounded [mpsc](sync::mpsc) channels implementing [Stream](futures::Stream).
+//!  - [broadcast](sync::broadcast) channel reporting lagging receivers.
+//!  - observable [Watch](value::Watch) value notifying about every change.
+//!  - fallible one-shot [TryAsyncValue](value::TryAsyncValue) resolving to [Result].
+//!  - asynchronous [spawn] (not requiring [Send] in WASM) with portable [JoinHandle],
+//!    [AbortHandle] and [JoinError], and [sleep](time::sleep),
+//!  - [spawn_local] for non-[Send] tasks on all targets, set up by [run_local] on native platforms,
+//!  - [spawn_blocking] for blocking closures, run on the next microtask in WASM,
+//!  - [JoinSet](task::JoinSet) managing group of spawned tasks,
+//!  - [yield_now](task::yield_now) and [Budget](task::Budget) for cooperative scheduling,
+//!  - [task_local] macro declaring [LocalKey](task::LocalKey)s with values scoped to futures,
+//!  - structured concurrency [scope] waiting for its tasks and cancelling them on failure,
 //!  - [Timeout](time::Timeout) future,
 //!  - [dtest](test::dtest) attribute macro to create tests for both
 //!    native and WASM targets, also [dtest_configure](test::dtest_configure)
@@ -26,14 +50,17 @@ pub use lock::*;
 
 pub mod value;
 
-#[cfg(not(target_arch = "wasm32"))]
-pub use tokio::{
-    spawn,
-    task::{JoinError, JoinHandle},
+pub mod sync;
+
+pub mod task;
+
+pub use task::{
+    run_local, scope, spawn, spawn_blocking, spawn_local, AbortHandle, JoinError, JoinHandle, Scope,
 };
 
-#[cfg(target_arch = "wasm32")]
-pub use js_utils::spawn::*;
+/// Declares new task-local keys of type [LocalKey](task::LocalKey).
+#[cfg(not(target_arch = "wasm32"))]
+pub use tokio::task_local;
 
 /// Utility macro for creating non-[Send] and non-[Sync] variants of traits
 /// for use in WASM.
@@ -0,0 +1,255 @@
+//! Lock API shared by all targets.
+//!
+//! Both native and WASM lock types implement these traits, so any method used through them
+//! is guaranteed to exist on every target. Inherent methods with the same names can be called
+//! without importing the traits.
+//!
+//! Deadlines of `try_*_until` methods are given as portable [Instant].
+
+use std::{
+    ops::{Deref, DerefMut},
+    time::Duration,
+};
+
+use crate::time::Instant;
+
+use super::{
+    Mutex, MutexGuard, RwLock, RwLockReadGuard, RwLockUpgradableReadGuard, RwLockWriteGuard,
+};
+
+/// API of [Mutex](crate::Mutex) available on all targets.
+pub trait MutexApi<T>: Sized {
+    /// Guard returned by [MutexApi::lock].
+    type Guard<'a>: DerefMut<Target = T>
+    where
+        Self: 'a;
+
+    /// Create new mutex.
+    fn new(value: T) -> Self;
+
+    /// Lock mutex.
+    fn lock(&self) -> Self::Guard<'_>;
+
+    /// Lock mutex or return [None] if it is already locked.
+    fn try_lock(&self) -> Option<Self::Guard<'_>>;
+
+    /// Lock mutex, waiting at most `timeout` for it to be released.
+    ///
+    /// Returns immediately in WASM, where waiting could never succeed.
+    fn try_lock_for(&self, timeout: Duration) -> Option<Self::Guard<'_>>;
+
+    /// Lock mutex, waiting until `timeout` at most for it to be released.
+    ///
+    /// Returns immediately in WASM, where waiting could never succeed.
+    fn try_lock_until(&self, timeout: Instant) -> Option<Self::Guard<'_>>;
+
+    /// Returns [true] if mutex is currently locked.
+    fn is_locked(&self) -> bool;
+
+    /// Return mutable reference to underlying data.
+    fn get_mut(&mut self) -> &mut T;
+
+    /// Consume mutex returning underlying data.
+    fn into_inner(self) -> T;
+}
+
+/// API of [RwLock](crate::RwLock) available on all targets.
+pub trait RwLockApi<T>: Sized {
+    /// Guard returned by [RwLockApi::read].
+    type ReadGuard<'a>: Deref<Target = T>
+    where
+        Self: 'a;
+
+    /// Guard returned by [RwLockApi::write].
+    type WriteGuard<'a>: DerefMut<Target = T>
+    where
+        Self: 'a;
+
+    /// Guard returned by [RwLockApi::upgradable_read].
+    type UpgradableReadGuard<'a>: Deref<Target = T>
+    where
+        Self: 'a;
+
+    /// Create new read-write lock.
+    fn new(value: T) -> Self;
+
+    /// Lock with shared read access.
+    fn read(&self) -> Self::ReadGuard<'_>;
+
+    /// Lock with shared read access or return [None] if it is locked exclusively.
+    fn try_read(&self) -> Option<Self::ReadGuard<'_>>;
+
+    /// Lock with shared read access, waiting at most `timeout` for it to be possible.
+    ///
+    /// Returns immediately in WASM, where waiting could never succeed.
+    fn try_read_for(&self, timeout: Duration) -> Option<Self::ReadGuard<'_>>;
+
+    /// Lock with shared read access, waiting until `timeout` at most for it to be possible.
+    ///
+    /// Returns immediately in WASM, where waiting could never succeed.
+    fn try_read_until(&self, timeout: Instant) -> Option<Self::ReadGuard<'_>>;
+
+    /// Lock with exclusive write access.
+    fn write(&self) -> Self::WriteGuard<'_>;
+
+    /// Lock with exclusive write access or return [None] if it is already locked.
+    fn try_write(&self) -> Option<Self::WriteGuard<'_>>;
+
+    /// Lock with exclusive write access, waiting at most `timeout` for it to be released.
+    ///
+    /// Returns immediately in WASM, where waiting could never succeed.
+    fn try_write_for(&self, timeout: Duration) -> Option<Self::WriteGuard<'_>>;
+
+    /// Lock with exclusive write access, waiting until `timeout` at most for it to be released.
+    ///
+    /// Returns immediately in WASM, where waiting could never succeed.
+    fn try_write_until(&self, timeout: Instant) -> Option<Self::WriteGuard<'_>>;
+
+    /// Lock with upgradable read access.
+    fn upgradable_read(&self) -> Self::UpgradableReadGuard<'_>;
+
+    /// Lock with upgradable read access or return [None] if not possible.
+    fn try_upgradable_read(&self) -> Option<Self::UpgradableReadGuard<'_>>;
+
+    /// Returns [true] if lock is currently held in any mode.
+    fn is_locked(&self) -> bool;
+
+    /// Returns [true] if lock is currently held exclusively.
+    fn is_locked_exclusive(&self) -> bool;
+
+    /// Return mutable reference to underlying data.
+    fn get_mut(&mut self) -> &mut T;
+
+    /// Consume lock returning underlying data.
+    fn into_inner(self) -> T;
+}
+
+impl<T> MutexApi<T> for Mutex<T> {
+    type Guard<'a>
+        = MutexGuard<'a, T>
+    where
+        Self: 'a;
+
+    fn new(value: T) -> Self {
+        Mutex::new(value)
+    }
+
+    #[track_caller]
+    fn lock(&self) -> Self::Guard<'_> {
+        Mutex::lock(self)
+    }
+
+    #[track_caller]
+    fn try_lock(&self) -> Option<Self::Guard<'_>> {
+        Mutex::try_lock(self)
+    }
+
+    #[track_caller]
+    fn try_lock_for(&self, timeout: Duration) -> Option<Self::Guard<'_>> {
+        Mutex::try_lock_for(self, timeout)
+    }
+
+    #[track_caller]
+    fn try_lock_until(&self, timeout: Instant) -> Option<Self::Guard<'_>> {
+        Mutex::try_lock_until(self, timeout)
+    }
+
+    fn is_locked(&self) -> bool {
+        Mutex::is_locked(self)
+    }
+
+    fn get_mut(&mut self) -> &mut T {
+        Mutex::get_mut(self)
+    }
+
+    fn into_inner(self) -> T {
+        Mutex::into_inner(self)
+    }
+}
+
+impl<T> RwLockApi<T> for RwLock<T> {
+    type ReadGuard<'a>
+        = RwLockReadGuard<'a, T>
+    where
+        Self: 'a;
+
+    type WriteGuard<'a>
+        = RwLockWriteGuard<'a, T>
+    where
+        Self: 'a;
+
+    type UpgradableReadGuard<'a>
+        = RwLockUpgradableReadGuard<'a, T>
+    where
+        Self: 'a;
+
+    fn new(value: T) -> Self {
+        RwLock::new(value)
+    }
+
+    #[track_caller]
+    fn read(&self) -> Self::ReadGuard<'_> {
+        RwLock::read(self)
+    }
+
+    #[track_caller]
+    fn try_read(&self) -> Option<Self::ReadGuard<'_>> {
+        RwLock::try_read(self)
+    }
+
+    #[track_caller]
+    fn try_read_for(&self, timeout: Duration) -> Option<Self::ReadGuard<'_>> {
+        RwLock::try_read_for(self, timeout)
+    }
+
+    #[track_caller]
+    fn try_read_until(&self, timeout: Instant) -> Option<Self::ReadGuard<'_>> {
+        RwLock::try_read_until(self, timeout)
+    }
+
+    #[track_caller]
+    fn write(&self) -> Self::WriteGuard<'_> {
+        RwLock::write(self)
+    }
+
+    #[track_caller]
+    fn try_write(&self) -> Option<Self::WriteGuard<'_>> {
+        RwLock::try_write(self)
+    }
+
+    #[track_caller]
+    fn try_write_for(&self, timeout: Duration) -> Option<Self::WriteGuard<'_>> {
+        RwLock::try_write_for(self, timeout)
+    }
+
+    #[track_caller]
+    fn try_write_until(&self, timeout: Instant) -> Option<Self::WriteGuard<'_>> {
+        RwLock::try_write_until(self, timeout)
+    }
+
+    #[track_caller]
+    fn upgradable_read(&self) -> Self::UpgradableReadGuard<'_> {
+        RwLock::upgradable_read(self)
+    }
+
+    #[track_caller]
+    fn try_upgradable_read(&self) -> Option<Self::UpgradableReadGuard<'_>> {
+        RwLock::try_upgradable_read(self)
+    }
+
+    fn is_locked(&self) -> bool {
+        RwLock::is_locked(self)
+    }
+
+    fn is_locked_exclusive(&self) -> bool {
+        RwLock::is_locked_exclusive(self)
+    }
+
+    fn get_mut(&mut self) -> &mut T {
+        RwLock::get_mut(self)
+    }
+
+    fn into_inner(self) -> T {
+        RwLock::into_inner(self)
+    }
+}
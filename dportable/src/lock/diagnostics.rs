@@ -0,0 +1,177 @@
+//! Debug-mode bookkeeping of outstanding lock guards.
+//!
+//! Re-entrant locking in WASM can only ever be a bug, so instead of `RefCell`'s generic
+//! `already borrowed` panic we report where the outstanding guards were acquired.
+//! On native platforms the same bookkeeping (kept per thread) turns a silent deadlock
+//! on a lock already held by the current thread into such a panic.
+
+use std::panic::Location;
+
+#[cfg(debug_assertions)]
+use std::{cell::RefCell, collections::HashMap};
+
+/// Kind of access granted by a guard.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Access {
+    Shared,
+    Upgradable,
+    Exclusive,
+}
+
+#[cfg(debug_assertions)]
+#[derive(Debug, Default)]
+struct Holders {
+    exclusive: Option<&'static Location<'static>>,
+    upgradable: Option<&'static Location<'static>>,
+    shared: Vec<&'static Location<'static>>,
+}
+
+#[cfg(debug_assertions)]
+impl Holders {
+    /// Returns [true] if acquiring `access` can't succeed while these guards are held.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn conflict(&self, access: Access) -> bool {
+        match access {
+            Access::Shared => self.exclusive.is_some(),
+            Access::Upgradable => self.exclusive.is_some() || self.upgradable.is_some(),
+            Access::Exclusive => true,
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.exclusive.is_none() && self.upgradable.is_none() && self.shared.is_empty()
+    }
+}
+
+#[cfg(debug_assertions)]
+thread_local! {
+    static HOLDERS: RefCell<HashMap<usize, Holders>> = RefCell::new(HashMap::new());
+}
+
+/// Registration of an outstanding guard, removed when dropped.
+#[derive(Debug)]
+pub(crate) struct Holder {
+    #[cfg(debug_assertions)]
+    key: usize,
+
+    #[cfg(debug_assertions)]
+    location: &'static Location<'static>,
+
+    #[cfg(debug_assertions)]
+    access: Access,
+}
+
+impl Holder {
+    /// Register guard with `access` to lock identified by `key`.
+    #[cfg_attr(not(debug_assertions), allow(unused_variables))]
+    pub(crate) fn new(key: usize, access: Access, location: &'static Location<'static>) -> Self {
+        #[cfg(debug_assertions)]
+        HOLDERS.with_borrow_mut(|holders| {
+            let entry = holders.entry(key).or_default();
+            match access {
+                Access::Shared => entry.shared.push(location),
+                Access::Upgradable => entry.upgradable = Some(location),
+                Access::Exclusive => entry.exclusive = Some(location),
+            }
+        });
+        Holder {
+            #[cfg(debug_assertions)]
+            key,
+            #[cfg(debug_assertions)]
+            location,
+            #[cfg(debug_assertions)]
+            access,
+        }
+    }
+
+    /// Register exclusive (mutable) guard of lock identified by `key`.
+    pub(crate) fn exclusive(key: usize, location: &'static Location<'static>) -> Self {
+        Holder::new(key, Access::Exclusive, location)
+    }
+
+    /// Register shared (read-only) guard of lock identified by `key`.
+    pub(crate) fn shared(key: usize, location: &'static Location<'static>) -> Self {
+        Holder::new(key, Access::Shared, location)
+    }
+
+    /// Register upgradable read guard of lock identified by `key`.
+    pub(crate) fn upgradable(key: usize, location: &'static Location<'static>) -> Self {
+        Holder::new(key, Access::Upgradable, location)
+    }
+}
+
+impl Drop for Holder {
+    fn drop(&mut self) {
+        #[cfg(debug_assertions)]
+        HOLDERS.with_borrow_mut(|holders| {
+            if let Some(entry) = holders.get_mut(&self.key) {
+                match self.access {
+                    Access::Shared => {
+                        if let Some(index) = entry
+                            .shared
+                            .iter()
+                            .position(|location| *location == self.location)
+                        {
+                            entry.shared.swap_remove(index);
+                        }
+                    }
+                    Access::Upgradable => entry.upgradable = None,
+                    Access::Exclusive => entry.exclusive = None,
+                }
+                if entry.is_empty() {
+                    holders.remove(&self.key);
+                }
+            }
+        });
+    }
+}
+
+/// Panic reporting contended lock acquisition.
+///
+/// In debug mode the message also lists locations of outstanding guards.
+#[cfg_attr(not(debug_assertions), allow(unused_variables))]
+#[cfg_attr(
+    all(not(target_arch = "wasm32"), not(debug_assertions)),
+    allow(dead_code)
+)]
+pub(crate) fn contended(lock: &str, key: usize, location: &'static Location<'static>) -> ! {
+    #[cfg(debug_assertions)]
+    {
+        let holders = HOLDERS.with_borrow(|holders| {
+            holders.get(&key).map(|holders| {
+                holders
+                    .exclusive
+                    .iter()
+                    .chain(holders.upgradable.iter())
+                    .chain(holders.shared.iter())
+                    .map(|location| location.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            })
+        });
+        if let Some(holders) = holders {
+            panic!("{lock} already locked at {holders}, attempted to lock again at {location}");
+        }
+    }
+    panic!("{lock} already locked, attempted to lock again at {location}")
+}
+
+/// In debug mode panic like [contended] if current thread holds guards
+/// that would prevent it from ever acquiring `access`.
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg_attr(not(debug_assertions), allow(unused_variables))]
+pub(crate) fn check_reentrant(
+    lock: &str,
+    key: usize,
+    access: Access,
+    location: &'static Location<'static>,
+) {
+    #[cfg(debug_assertions)]
+    if HOLDERS.with_borrow(|holders| {
+        holders
+            .get(&key)
+            .is_some_and(|holders| holders.conflict(access))
+    }) {
+        contended(lock, key, location)
+    }
+}
@@ -1,4 +1,10 @@
-//! Mutex and RwLock.
+//! Mutex and RwLock, blocking and async.
+
+mod api;
+pub use api::*;
+
+mod timeout;
+pub use timeout::*;
 
 #[cfg(not(target_arch = "wasm32"))]
 mod native;
@@ -6,16 +12,34 @@ mod native;
 #[cfg(not(target_arch = "wasm32"))]
 pub use native::*;
 
+mod diagnostics;
+
 #[cfg(target_arch = "wasm32")]
 mod wasm;
 
 #[cfg(target_arch = "wasm32")]
 pub use wasm::*;
 
+#[cfg(target_arch = "wasm32")]
+mod wasm_async;
+
+#[cfg(target_arch = "wasm32")]
+pub use wasm_async::*;
+
 #[cfg(test)]
 mod tests {
-    use super::{Mutex, RwLock};
-    use crate::test::{dtest, dtest_configure};
+    use std::{fmt::Debug, sync::Arc, time::Duration};
+
+    use super::{
+        AsyncMutex, AsyncMutexExt, AsyncRwLock, AsyncRwLockExt, LockTimedOut, MappedMutexGuard,
+        Mutex, MutexApi, MutexGuard, RwLock, RwLockApi, RwLockReadGuard, RwLockUpgradableReadGuard,
+        RwLockWriteGuard,
+    };
+    use crate::{
+        spawn,
+        test::{dtest, dtest_configure},
+        time::{sleep, Instant, Timeout},
+    };
 
     dtest_configure!();
 
@@ -36,4 +60,267 @@ mod tests {
         *a.write() = 4;
         assert_eq!(4, *a.read());
     }
+
+    fn deadline() -> Instant {
+        Instant::now() + Duration::from_millis(10)
+    }
+
+    fn check_mutex_api<M>()
+    where
+        M: MutexApi<i32> + Default + From<i32> + Debug,
+    {
+        let mut mutex = M::from(2);
+        assert!(!mutex.is_locked());
+        let guard = mutex.lock();
+        assert!(mutex.is_locked());
+        assert!(mutex.try_lock().is_none());
+        assert!(mutex.try_lock_for(Duration::from_millis(10)).is_none());
+        assert!(mutex.try_lock_until(deadline()).is_none());
+        drop(guard);
+        assert!(mutex.try_lock_for(Duration::from_millis(10)).is_some());
+        assert!(mutex.try_lock_until(deadline()).is_some());
+        *mutex.try_lock().unwrap() = 3;
+        println!("{:?}", mutex);
+        *mutex.get_mut() += 1;
+        assert_eq!(4, mutex.into_inner());
+        assert_eq!(5, M::new(5).into_inner());
+        assert_eq!(0, M::default().into_inner());
+    }
+
+    fn check_rw_lock_api<L>()
+    where
+        L: RwLockApi<i32> + Default + From<i32> + Debug,
+    {
+        let mut lock = L::from(2);
+        assert!(!lock.is_locked());
+        let guard = lock.read();
+        assert!(lock.is_locked());
+        assert!(!lock.is_locked_exclusive());
+        assert!(lock.try_read().is_some());
+        assert!(lock.try_read_for(Duration::from_millis(10)).is_some());
+        assert!(lock.try_read_until(deadline()).is_some());
+        assert!(lock.try_write().is_none());
+        assert!(lock.try_write_for(Duration::from_millis(10)).is_none());
+        assert!(lock.try_write_until(deadline()).is_none());
+        drop(guard);
+        assert!(lock.try_write_for(Duration::from_millis(10)).is_some());
+        assert!(lock.try_write_until(deadline()).is_some());
+        let guard = lock.write();
+        assert!(lock.try_read_until(deadline()).is_none());
+        drop(guard);
+        let guard = lock.upgradable_read();
+        assert!(lock.try_upgradable_read().is_none());
+        drop(guard);
+        *lock.write() = 3;
+        let guard = lock.try_write().unwrap();
+        assert!(lock.is_locked_exclusive());
+        assert!(lock.try_read().is_none());
+        assert!(lock.try_upgradable_read().is_none());
+        drop(guard);
+        println!("{:?}", lock);
+        *lock.get_mut() += 1;
+        assert_eq!(4, lock.into_inner());
+        assert_eq!(5, L::new(5).into_inner());
+        assert_eq!(0, L::default().into_inner());
+    }
+
+    #[dtest]
+    async fn test_api_parity() {
+        check_mutex_api::<Mutex<i32>>();
+        check_rw_lock_api::<RwLock<i32>>();
+    }
+
+    #[dtest]
+    async fn test_mapped_guards() {
+        let a = Mutex::new((1, vec![2]));
+        let mut guard = MutexGuard::map(a.lock(), |value| &mut value.1);
+        guard.push(3);
+        let guard = MappedMutexGuard::map(guard, |value| value.as_mut_slice());
+        assert_eq!([2, 3], *guard);
+        drop(guard);
+        let guard = MutexGuard::try_map(a.lock(), |value| value.1.get_mut(5));
+        assert!(guard.is_err());
+
+        let a = RwLock::new((1, 2));
+        let guard = RwLockReadGuard::map(a.read(), |value| &value.0);
+        assert_eq!(1, *guard);
+        drop(guard);
+        let mut guard = RwLockWriteGuard::map(a.write(), |value| &mut value.1);
+        *guard = 4;
+        drop(guard);
+        assert_eq!((1, 4), *a.read());
+    }
+
+    #[dtest]
+    async fn test_unsized_mapped_guards() {
+        let a: Box<Mutex<[i32]>> = Box::new(Mutex::new([1, 2, 3]));
+        let mut guard = MutexGuard::map(a.lock(), |value| &mut value[1]);
+        *guard = 4;
+        drop(guard);
+        assert_eq!([1, 4, 3], *a.lock());
+
+        let a: Box<RwLock<[i32]>> = Box::new(RwLock::new([1, 2, 3]));
+        let guard = RwLockReadGuard::map(a.read(), |value| &value[0]);
+        assert_eq!(1, *guard);
+        drop(guard);
+        let mut guard = RwLockWriteGuard::map(a.write(), |value| &mut value[2]);
+        *guard = 5;
+        drop(guard);
+        let guard = RwLockUpgradableReadGuard::upgrade(a.upgradable_read());
+        assert_eq!([1, 2, 5], *guard);
+    }
+
+    #[dtest]
+    async fn test_upgradable_read() {
+        let a = RwLock::new(2);
+        let guard = a.upgradable_read();
+        let read_guard = a.try_read().unwrap();
+        assert!(a.try_upgradable_read().is_none());
+        assert!(a.try_write().is_none());
+
+        let guard = RwLockUpgradableReadGuard::try_upgrade(guard).unwrap_err();
+        drop(read_guard);
+        let mut guard = RwLockUpgradableReadGuard::upgrade(guard);
+        *guard = 4;
+        let guard = RwLockWriteGuard::downgrade(guard);
+        assert_eq!(4, *guard);
+        assert!(a.try_read().is_some());
+        assert!(a.try_write().is_none());
+        drop(guard);
+
+        let guard = RwLockUpgradableReadGuard::downgrade(a.upgradable_read());
+        assert!(a.try_upgradable_read().is_some());
+        drop(guard);
+        assert!(a.try_write().is_some());
+    }
+
+    #[dtest]
+    async fn test_async_mutex() {
+        let a = AsyncMutex::new(2);
+        println!("{}", a.lock().await);
+        assert_eq!(2, *a.lock().await);
+        *a.lock().await = 4;
+        assert_eq!(4, *a.lock().await);
+
+        let guard = a.lock().await;
+        assert!(a.try_lock().is_err());
+        drop(guard);
+        assert!(a.try_lock().is_ok());
+    }
+
+    #[dtest]
+    async fn test_async_mutex_across_await() {
+        let a = Arc::new(AsyncMutex::new(0));
+        let mut guard = a.clone().lock_owned().await;
+        let a_clone = a.clone();
+        let join_handle = spawn(async move {
+            let mut guard = a_clone.lock().await;
+            *guard += 1;
+            *guard
+        });
+        sleep(Duration::from_millis(10)).await;
+        *guard += 10;
+        drop(guard);
+        assert_eq!(11, join_handle.await.unwrap());
+        assert_eq!(11, *a.try_lock_owned().unwrap());
+    }
+
+    #[dtest]
+    async fn test_async_rw_lock() {
+        let a = AsyncRwLock::new(2);
+        println!("{}", a.read().await);
+        assert_eq!(2, *a.read().await);
+        *a.write().await = 4;
+        assert_eq!(4, *a.read().await);
+
+        let guard_1 = a.read().await;
+        let guard_2 = a.try_read().unwrap();
+        assert!(a.try_write().is_err());
+        drop(guard_1);
+        drop(guard_2);
+        assert!(a.try_write().is_ok());
+    }
+
+    #[dtest]
+    async fn test_async_rw_lock_writer_queueing() {
+        let a = Arc::new(AsyncRwLock::new(Vec::new()));
+        let guard = a.read().await;
+
+        let a_clone = a.clone();
+        let writer_1 = spawn(async move { a_clone.write().await.push(1) });
+        sleep(Duration::from_millis(10)).await;
+        let a_clone = a.clone();
+        let writer_2 = spawn(async move { a_clone.write().await.push(2) });
+        sleep(Duration::from_millis(10)).await;
+
+        // Queued writers take precedence over new readers.
+        assert!(a.try_read().is_err());
+        let a_clone = a.clone();
+        let reader = spawn(async move { a_clone.read().await.clone() });
+        sleep(Duration::from_millis(10)).await;
+
+        drop(guard);
+        writer_1.await.unwrap();
+        writer_2.await.unwrap();
+        assert_eq!(vec![1, 2], reader.await.unwrap());
+    }
+
+    #[cfg(debug_assertions)]
+    #[dtest]
+    #[should_panic(expected = "Mutex already locked at")]
+    async fn test_mutex_reentrancy_diagnostics() {
+        let a = Mutex::new(2);
+        let _guard = a.lock();
+        let _other_guard = a.lock();
+    }
+
+    #[cfg(debug_assertions)]
+    #[dtest]
+    #[should_panic(expected = "RwLock already locked at")]
+    async fn test_rw_lock_reentrancy_diagnostics() {
+        let a = RwLock::new(2);
+        let _guard = a.read();
+        let _other_guard = a.write();
+    }
+
+    #[cfg(all(feature = "deadlock_detection", not(target_arch = "wasm32")))]
+    #[test]
+    fn test_deadlock_detection() {
+        use std::sync::Barrier;
+
+        let a = Arc::new(Mutex::new(()));
+        let b = Arc::new(Mutex::new(()));
+        let barrier = Arc::new(Barrier::new(2));
+        for (first, second) in [(a.clone(), b.clone()), (b, a)] {
+            let barrier = barrier.clone();
+            std::thread::spawn(move || {
+                let _first = first.lock();
+                barrier.wait();
+                let _second = second.lock();
+            });
+        }
+
+        let detector = super::spawn_deadlock_detector(Duration::from_millis(10));
+        assert!(detector.join().is_err());
+    }
+
+    #[dtest]
+    async fn test_async_lock_timeout() {
+        let a = AsyncMutex::new(2);
+        let guard = a.lock().await;
+        assert_eq!(
+            LockTimedOut {},
+            a.lock_timeout(Duration::from_millis(10)).await.unwrap_err()
+        );
+        drop(guard);
+        assert_eq!(2, *a.lock_timeout(Duration::from_millis(10)).await.unwrap());
+
+        let a = AsyncRwLock::new(2);
+        let guard = a.read().await;
+        assert_eq!(2, *a.read_timeout(Duration::from_millis(10)).await.unwrap());
+        assert!(a.write_timeout(Duration::from_millis(10)).await.is_err());
+        drop(guard);
+        *a.write_timeout(Timeout::never()).await.unwrap() = 4;
+        assert_eq!(4, *a.read().await);
+    }
 }
@@ -1 +1,759 @@
-pub use parking_lot::{Mutex, MutexGuard, RwLock, RwLockReadGuard, RwLockWriteGuard};
+use std::{
+    fmt::Display,
+    ops::{Deref, DerefMut},
+    panic::Location,
+    time::Duration,
+};
+
+pub use tokio::sync::{
+    Mutex as AsyncMutex, MutexGuard as AsyncMutexGuard, OwnedMutexGuard as OwnedAsyncMutexGuard,
+    RwLock as AsyncRwLock, RwLockReadGuard as AsyncRwLockReadGuard,
+    RwLockWriteGuard as AsyncRwLockWriteGuard, TryLockError,
+};
+
+use crate::time::Instant;
+
+use super::diagnostics::{check_reentrant, Access, Holder};
+
+#[cfg(feature = "deadlock_detection")]
+pub use parking_lot::deadlock;
+
+/// Spawn background thread checking for deadlocks every `interval`.
+///
+/// Once a deadlock is found the thread panics with backtraces of all deadlocked threads.
+#[cfg(feature = "deadlock_detection")]
+pub fn spawn_deadlock_detector(interval: std::time::Duration) -> std::thread::JoinHandle<()> {
+    use std::fmt::Write;
+
+    std::thread::spawn(move || loop {
+        std::thread::sleep(interval);
+        let deadlocks = deadlock::check_deadlock();
+        if deadlocks.is_empty() {
+            continue;
+        }
+
+        let mut message = format!("{} deadlock(s) detected", deadlocks.len());
+        for (index, threads) in deadlocks.iter().enumerate() {
+            let _ = write!(message, "\nDeadlock #{index}");
+            for thread in threads {
+                let _ = write!(
+                    message,
+                    "\nThread {:?}:\n{:?}",
+                    thread.thread_id(),
+                    thread.backtrace()
+                );
+            }
+        }
+        panic!("{message}");
+    })
+}
+
+/// Mutual exclusion primitive (using [parking_lot::Mutex]).
+///
+/// In debug builds locking a mutex already held by the current thread panics
+/// with locations of both the outstanding guard and the contender, instead of deadlocking.
+#[derive(Debug)]
+pub struct Mutex<T>(parking_lot::Mutex<T>)
+where
+    T: ?Sized;
+
+/// Guard of [Mutex], releasing it when dropped.
+#[derive(Debug)]
+pub struct MutexGuard<'a, T>(parking_lot::MutexGuard<'a, T>, #[allow(dead_code)] Holder)
+where
+    T: ?Sized + 'a;
+
+impl<T> Deref for MutexGuard<'_, T>
+where
+    T: ?Sized,
+{
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for MutexGuard<'_, T>
+where
+    T: ?Sized,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<T> Display for MutexGuard<'_, T>
+where
+    T: ?Sized + Display,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(&*self.0, f)
+    }
+}
+
+impl<'a, T> MutexGuard<'a, T>
+where
+    T: ?Sized,
+{
+    /// Make new guard for a component of locked data.
+    pub fn map<U, F>(s: Self, f: F) -> MappedMutexGuard<'a, U>
+    where
+        U: ?Sized,
+        F: FnOnce(&mut T) -> &mut U,
+    {
+        MappedMutexGuard(parking_lot::MutexGuard::map(s.0, f), s.1)
+    }
+
+    /// Make new guard for a component of locked data or return original guard
+    /// if `f` returns [None].
+    pub fn try_map<U, F>(s: Self, f: F) -> Result<MappedMutexGuard<'a, U>, Self>
+    where
+        U: ?Sized,
+        F: FnOnce(&mut T) -> Option<&mut U>,
+    {
+        match parking_lot::MutexGuard::try_map(s.0, f) {
+            Ok(value) => Ok(MappedMutexGuard(value, s.1)),
+            Err(value) => Err(MutexGuard(value, s.1)),
+        }
+    }
+}
+
+/// Guard of a component of [Mutex] data, returned by [MutexGuard::map].
+#[derive(Debug)]
+pub struct MappedMutexGuard<'a, T>(
+    parking_lot::MappedMutexGuard<'a, T>,
+    #[allow(dead_code)] Holder,
+)
+where
+    T: ?Sized + 'a;
+
+impl<'a, T> MappedMutexGuard<'a, T>
+where
+    T: ?Sized,
+{
+    /// Make new guard for a component of locked data.
+    pub fn map<U, F>(s: Self, f: F) -> MappedMutexGuard<'a, U>
+    where
+        U: ?Sized,
+        F: FnOnce(&mut T) -> &mut U,
+    {
+        MappedMutexGuard(parking_lot::MappedMutexGuard::map(s.0, f), s.1)
+    }
+
+    /// Make new guard for a component of locked data or return original guard
+    /// if `f` returns [None].
+    pub fn try_map<U, F>(s: Self, f: F) -> Result<MappedMutexGuard<'a, U>, Self>
+    where
+        U: ?Sized,
+        F: FnOnce(&mut T) -> Option<&mut U>,
+    {
+        match parking_lot::MappedMutexGuard::try_map(s.0, f) {
+            Ok(value) => Ok(MappedMutexGuard(value, s.1)),
+            Err(value) => Err(MappedMutexGuard(value, s.1)),
+        }
+    }
+}
+
+impl<T> Deref for MappedMutexGuard<'_, T>
+where
+    T: ?Sized,
+{
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for MappedMutexGuard<'_, T>
+where
+    T: ?Sized,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<T> Display for MappedMutexGuard<'_, T>
+where
+    T: ?Sized + Display,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(&*self.0, f)
+    }
+}
+
+impl<T> Mutex<T> {
+    /// Create new mutex.
+    pub fn new(value: T) -> Self {
+        Mutex(parking_lot::Mutex::new(value))
+    }
+
+    /// Consume mutex returning underlying data.
+    pub fn into_inner(self) -> T {
+        self.0.into_inner()
+    }
+}
+
+impl<T> Mutex<T>
+where
+    T: ?Sized,
+{
+    /// Lock mutex, blocking current thread until it is released.
+    #[track_caller]
+    pub fn lock(&self) -> MutexGuard<'_, T> {
+        let location = Location::caller();
+        let guard = self.0.try_lock().unwrap_or_else(|| {
+            check_reentrant("Mutex", self.key(), Access::Exclusive, location);
+            self.0.lock()
+        });
+        MutexGuard(guard, Holder::exclusive(self.key(), location))
+    }
+
+    /// Lock mutex or return [None] if it is already locked.
+    #[track_caller]
+    pub fn try_lock(&self) -> Option<MutexGuard<'_, T>> {
+        let location = Location::caller();
+        self.0
+            .try_lock()
+            .map(|guard| MutexGuard(guard, Holder::exclusive(self.key(), location)))
+    }
+
+    /// Lock mutex, waiting at most `timeout` for it to be released.
+    #[track_caller]
+    pub fn try_lock_for(&self, timeout: Duration) -> Option<MutexGuard<'_, T>> {
+        let location = Location::caller();
+        self.0
+            .try_lock_for(timeout)
+            .map(|guard| MutexGuard(guard, Holder::exclusive(self.key(), location)))
+    }
+
+    /// Lock mutex, waiting until `timeout` at most for it to be released.
+    #[track_caller]
+    pub fn try_lock_until(&self, timeout: Instant) -> Option<MutexGuard<'_, T>> {
+        let location = Location::caller();
+        self.0
+            .try_lock_until(timeout.into_std())
+            .map(|guard| MutexGuard(guard, Holder::exclusive(self.key(), location)))
+    }
+
+    /// Returns [true] if mutex is currently locked.
+    pub fn is_locked(&self) -> bool {
+        self.0.is_locked()
+    }
+
+    /// Return mutable reference to underlying data.
+    pub fn get_mut(&mut self) -> &mut T {
+        self.0.get_mut()
+    }
+
+    fn key(&self) -> usize {
+        self as *const Self as *const () as usize
+    }
+}
+
+impl<T> Default for Mutex<T>
+where
+    T: Default,
+{
+    fn default() -> Self {
+        Mutex::new(T::default())
+    }
+}
+
+impl<T> From<T> for Mutex<T> {
+    fn from(value: T) -> Self {
+        Mutex::new(value)
+    }
+}
+
+/// Reader-writer lock (using [parking_lot::RwLock]).
+///
+/// In debug builds acquiring access conflicting with guards held by the current thread panics
+/// with locations of both the outstanding guards and the contender, instead of deadlocking.
+#[derive(Debug)]
+pub struct RwLock<T>(parking_lot::RwLock<T>)
+where
+    T: ?Sized;
+
+/// Guard of [RwLock] shared read access, releasing it when dropped.
+#[derive(Debug)]
+pub struct RwLockReadGuard<'a, T>(
+    parking_lot::RwLockReadGuard<'a, T>,
+    #[allow(dead_code)] Holder,
+)
+where
+    T: ?Sized + 'a;
+
+impl<T> Display for RwLockReadGuard<'_, T>
+where
+    T: ?Sized + Display,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(&*self.0, f)
+    }
+}
+
+impl<T> Deref for RwLockReadGuard<'_, T>
+where
+    T: ?Sized,
+{
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<'a, T> RwLockReadGuard<'a, T>
+where
+    T: ?Sized,
+{
+    /// Make new guard for a component of locked data.
+    pub fn map<U, F>(s: Self, f: F) -> MappedRwLockReadGuard<'a, U>
+    where
+        U: ?Sized,
+        F: FnOnce(&T) -> &U,
+    {
+        MappedRwLockReadGuard(parking_lot::RwLockReadGuard::map(s.0, f), s.1)
+    }
+
+    /// Make new guard for a component of locked data or return original guard
+    /// if `f` returns [None].
+    pub fn try_map<U, F>(s: Self, f: F) -> Result<MappedRwLockReadGuard<'a, U>, Self>
+    where
+        U: ?Sized,
+        F: FnOnce(&T) -> Option<&U>,
+    {
+        match parking_lot::RwLockReadGuard::try_map(s.0, f) {
+            Ok(value) => Ok(MappedRwLockReadGuard(value, s.1)),
+            Err(value) => Err(RwLockReadGuard(value, s.1)),
+        }
+    }
+}
+
+/// Guard of a component of [RwLock] data, returned by [RwLockReadGuard::map].
+#[derive(Debug)]
+pub struct MappedRwLockReadGuard<'a, T>(
+    parking_lot::MappedRwLockReadGuard<'a, T>,
+    #[allow(dead_code)] Holder,
+)
+where
+    T: ?Sized + 'a;
+
+impl<'a, T> MappedRwLockReadGuard<'a, T>
+where
+    T: ?Sized,
+{
+    /// Make new guard for a component of locked data.
+    pub fn map<U, F>(s: Self, f: F) -> MappedRwLockReadGuard<'a, U>
+    where
+        U: ?Sized,
+        F: FnOnce(&T) -> &U,
+    {
+        MappedRwLockReadGuard(parking_lot::MappedRwLockReadGuard::map(s.0, f), s.1)
+    }
+
+    /// Make new guard for a component of locked data or return original guard
+    /// if `f` returns [None].
+    pub fn try_map<U, F>(s: Self, f: F) -> Result<MappedRwLockReadGuard<'a, U>, Self>
+    where
+        U: ?Sized,
+        F: FnOnce(&T) -> Option<&U>,
+    {
+        match parking_lot::MappedRwLockReadGuard::try_map(s.0, f) {
+            Ok(value) => Ok(MappedRwLockReadGuard(value, s.1)),
+            Err(value) => Err(MappedRwLockReadGuard(value, s.1)),
+        }
+    }
+}
+
+impl<T> Deref for MappedRwLockReadGuard<'_, T>
+where
+    T: ?Sized,
+{
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> Display for MappedRwLockReadGuard<'_, T>
+where
+    T: ?Sized + Display,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(&*self.0, f)
+    }
+}
+
+/// Guard of [RwLock] upgradable read access, releasing it when dropped.
+#[derive(Debug)]
+pub struct RwLockUpgradableReadGuard<'a, T>(
+    parking_lot::RwLockUpgradableReadGuard<'a, T>,
+    #[allow(dead_code)] Holder,
+    &'a RwLock<T>,
+)
+where
+    T: ?Sized + 'a;
+
+impl<'a, T> RwLockUpgradableReadGuard<'a, T>
+where
+    T: ?Sized,
+{
+    /// Upgrade to exclusive write access, blocking current thread until other readers are gone.
+    #[track_caller]
+    pub fn upgrade(s: Self) -> RwLockWriteGuard<'a, T> {
+        let location = Location::caller();
+        let RwLockUpgradableReadGuard(guard, holder, lock) = s;
+        drop(holder);
+        let guard =
+            parking_lot::RwLockUpgradableReadGuard::try_upgrade(guard).unwrap_or_else(|guard| {
+                check_reentrant("RwLock", lock.key(), Access::Exclusive, location);
+                parking_lot::RwLockUpgradableReadGuard::upgrade(guard)
+            });
+        RwLockWriteGuard(guard, Holder::exclusive(lock.key(), location), lock)
+    }
+
+    /// Upgrade to exclusive write access or return original guard if there are other readers.
+    #[track_caller]
+    pub fn try_upgrade(s: Self) -> Result<RwLockWriteGuard<'a, T>, Self> {
+        let location = Location::caller();
+        let RwLockUpgradableReadGuard(guard, holder, lock) = s;
+        match parking_lot::RwLockUpgradableReadGuard::try_upgrade(guard) {
+            Ok(guard) => {
+                drop(holder);
+                Ok(RwLockWriteGuard(
+                    guard,
+                    Holder::exclusive(lock.key(), location),
+                    lock,
+                ))
+            }
+            Err(guard) => Err(RwLockUpgradableReadGuard(guard, holder, lock)),
+        }
+    }
+
+    /// Downgrade to shared read access.
+    #[track_caller]
+    pub fn downgrade(s: Self) -> RwLockReadGuard<'a, T> {
+        let location = Location::caller();
+        let RwLockUpgradableReadGuard(guard, holder, lock) = s;
+        drop(holder);
+        RwLockReadGuard(
+            parking_lot::RwLockUpgradableReadGuard::downgrade(guard),
+            Holder::shared(lock.key(), location),
+        )
+    }
+}
+
+impl<T> Deref for RwLockUpgradableReadGuard<'_, T>
+where
+    T: ?Sized,
+{
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> Display for RwLockUpgradableReadGuard<'_, T>
+where
+    T: ?Sized + Display,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(&*self.0, f)
+    }
+}
+
+/// Guard of [RwLock] exclusive write access, releasing it when dropped.
+#[derive(Debug)]
+pub struct RwLockWriteGuard<'a, T>(
+    parking_lot::RwLockWriteGuard<'a, T>,
+    #[allow(dead_code)] Holder,
+    &'a RwLock<T>,
+)
+where
+    T: ?Sized + 'a;
+
+impl<T> Deref for RwLockWriteGuard<'_, T>
+where
+    T: ?Sized,
+{
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for RwLockWriteGuard<'_, T>
+where
+    T: ?Sized,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<T> Display for RwLockWriteGuard<'_, T>
+where
+    T: ?Sized + Display,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(&*self.0, f)
+    }
+}
+
+impl<'a, T> RwLockWriteGuard<'a, T>
+where
+    T: ?Sized,
+{
+    /// Make new guard for a component of locked data.
+    pub fn map<U, F>(s: Self, f: F) -> MappedRwLockWriteGuard<'a, U>
+    where
+        U: ?Sized,
+        F: FnOnce(&mut T) -> &mut U,
+    {
+        MappedRwLockWriteGuard(parking_lot::RwLockWriteGuard::map(s.0, f), s.1)
+    }
+
+    /// Make new guard for a component of locked data or return original guard
+    /// if `f` returns [None].
+    pub fn try_map<U, F>(s: Self, f: F) -> Result<MappedRwLockWriteGuard<'a, U>, Self>
+    where
+        U: ?Sized,
+        F: FnOnce(&mut T) -> Option<&mut U>,
+    {
+        match parking_lot::RwLockWriteGuard::try_map(s.0, f) {
+            Ok(value) => Ok(MappedRwLockWriteGuard(value, s.1)),
+            Err(value) => Err(RwLockWriteGuard(value, s.1, s.2)),
+        }
+    }
+
+    /// Downgrade to shared read access.
+    #[track_caller]
+    pub fn downgrade(s: Self) -> RwLockReadGuard<'a, T> {
+        let location = Location::caller();
+        let RwLockWriteGuard(guard, holder, lock) = s;
+        drop(holder);
+        RwLockReadGuard(
+            parking_lot::RwLockWriteGuard::downgrade(guard),
+            Holder::shared(lock.key(), location),
+        )
+    }
+}
+
+/// Guard of a component of [RwLock] data, returned by [RwLockWriteGuard::map].
+#[derive(Debug)]
+pub struct MappedRwLockWriteGuard<'a, T>(
+    parking_lot::MappedRwLockWriteGuard<'a, T>,
+    #[allow(dead_code)] Holder,
+)
+where
+    T: ?Sized + 'a;
+
+impl<'a, T> MappedRwLockWriteGuard<'a, T>
+where
+    T: ?Sized,
+{
+    /// Make new guard for a component of locked data.
+    pub fn map<U, F>(s: Self, f: F) -> MappedRwLockWriteGuard<'a, U>
+    where
+        U: ?Sized,
+        F: FnOnce(&mut T) -> &mut U,
+    {
+        MappedRwLockWriteGuard(parking_lot::MappedRwLockWriteGuard::map(s.0, f), s.1)
+    }
+
+    /// Make new guard for a component of locked data or return original guard
+    /// if `f` returns [None].
+    pub fn try_map<U, F>(s: Self, f: F) -> Result<MappedRwLockWriteGuard<'a, U>, Self>
+    where
+        U: ?Sized,
+        F: FnOnce(&mut T) -> Option<&mut U>,
+    {
+        match parking_lot::MappedRwLockWriteGuard::try_map(s.0, f) {
+            Ok(value) => Ok(MappedRwLockWriteGuard(value, s.1)),
+            Err(value) => Err(MappedRwLockWriteGuard(value, s.1)),
+        }
+    }
+}
+
+impl<T> Deref for MappedRwLockWriteGuard<'_, T>
+where
+    T: ?Sized,
+{
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for MappedRwLockWriteGuard<'_, T>
+where
+    T: ?Sized,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<T> Display for MappedRwLockWriteGuard<'_, T>
+where
+    T: ?Sized + Display,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(&*self.0, f)
+    }
+}
+
+impl<T> RwLock<T> {
+    /// Create new read-write lock.
+    pub fn new(value: T) -> Self {
+        RwLock(parking_lot::RwLock::new(value))
+    }
+
+    /// Consume lock returning underlying data.
+    pub fn into_inner(self) -> T {
+        self.0.into_inner()
+    }
+}
+
+impl<T> RwLock<T>
+where
+    T: ?Sized,
+{
+    /// Lock with shared read access, blocking current thread until it is possible.
+    #[track_caller]
+    pub fn read(&self) -> RwLockReadGuard<'_, T> {
+        let location = Location::caller();
+        let guard = self.0.try_read().unwrap_or_else(|| {
+            check_reentrant("RwLock", self.key(), Access::Shared, location);
+            self.0.read()
+        });
+        RwLockReadGuard(guard, Holder::shared(self.key(), location))
+    }
+
+    /// Lock with shared read access or return [None] if it is locked exclusively.
+    #[track_caller]
+    pub fn try_read(&self) -> Option<RwLockReadGuard<'_, T>> {
+        let location = Location::caller();
+        self.0
+            .try_read()
+            .map(|guard| RwLockReadGuard(guard, Holder::shared(self.key(), location)))
+    }
+
+    /// Lock with shared read access, waiting at most `timeout` for it to be possible.
+    #[track_caller]
+    pub fn try_read_for(&self, timeout: Duration) -> Option<RwLockReadGuard<'_, T>> {
+        let location = Location::caller();
+        self.0
+            .try_read_for(timeout)
+            .map(|guard| RwLockReadGuard(guard, Holder::shared(self.key(), location)))
+    }
+
+    /// Lock with shared read access, waiting until `timeout` at most for it to be possible.
+    #[track_caller]
+    pub fn try_read_until(&self, timeout: Instant) -> Option<RwLockReadGuard<'_, T>> {
+        let location = Location::caller();
+        self.0
+            .try_read_until(timeout.into_std())
+            .map(|guard| RwLockReadGuard(guard, Holder::shared(self.key(), location)))
+    }
+
+    /// Lock with exclusive write access, blocking current thread until it is released.
+    #[track_caller]
+    pub fn write(&self) -> RwLockWriteGuard<'_, T> {
+        let location = Location::caller();
+        let guard = self.0.try_write().unwrap_or_else(|| {
+            check_reentrant("RwLock", self.key(), Access::Exclusive, location);
+            self.0.write()
+        });
+        RwLockWriteGuard(guard, Holder::exclusive(self.key(), location), self)
+    }
+
+    /// Lock with exclusive write access or return [None] if it is already locked.
+    #[track_caller]
+    pub fn try_write(&self) -> Option<RwLockWriteGuard<'_, T>> {
+        let location = Location::caller();
+        self.0
+            .try_write()
+            .map(|guard| RwLockWriteGuard(guard, Holder::exclusive(self.key(), location), self))
+    }
+
+    /// Lock with exclusive write access, waiting at most `timeout` for it to be released.
+    #[track_caller]
+    pub fn try_write_for(&self, timeout: Duration) -> Option<RwLockWriteGuard<'_, T>> {
+        let location = Location::caller();
+        self.0
+            .try_write_for(timeout)
+            .map(|guard| RwLockWriteGuard(guard, Holder::exclusive(self.key(), location), self))
+    }
+
+    /// Lock with exclusive write access, waiting until `timeout` at most for it to be released.
+    #[track_caller]
+    pub fn try_write_until(&self, timeout: Instant) -> Option<RwLockWriteGuard<'_, T>> {
+        let location = Location::caller();
+        self.0
+            .try_write_until(timeout.into_std())
+            .map(|guard| RwLockWriteGuard(guard, Holder::exclusive(self.key(), location), self))
+    }
+
+    /// Lock with upgradable read access, blocking current thread until it is possible.
+    #[track_caller]
+    pub fn upgradable_read(&self) -> RwLockUpgradableReadGuard<'_, T> {
+        let location = Location::caller();
+        let guard = self.0.try_upgradable_read().unwrap_or_else(|| {
+            check_reentrant("RwLock", self.key(), Access::Upgradable, location);
+            self.0.upgradable_read()
+        });
+        RwLockUpgradableReadGuard(guard, Holder::upgradable(self.key(), location), self)
+    }
+
+    /// Lock with upgradable read access or return [None] if not possible.
+    #[track_caller]
+    pub fn try_upgradable_read(&self) -> Option<RwLockUpgradableReadGuard<'_, T>> {
+        let location = Location::caller();
+        self.0.try_upgradable_read().map(|guard| {
+            RwLockUpgradableReadGuard(guard, Holder::upgradable(self.key(), location), self)
+        })
+    }
+
+    /// Returns [true] if lock is currently held in any mode.
+    pub fn is_locked(&self) -> bool {
+        self.0.is_locked()
+    }
+
+    /// Returns [true] if lock is currently held exclusively.
+    pub fn is_locked_exclusive(&self) -> bool {
+        self.0.is_locked_exclusive()
+    }
+
+    /// Return mutable reference to underlying data.
+    pub fn get_mut(&mut self) -> &mut T {
+        self.0.get_mut()
+    }
+
+    fn key(&self) -> usize {
+        self as *const Self as *const () as usize
+    }
+}
+
+impl<T> Default for RwLock<T>
+where
+    T: Default,
+{
+    fn default() -> Self {
+        RwLock::new(T::default())
+    }
+}
+
+impl<T> From<T> for RwLock<T> {
+    fn from(value: T) -> Self {
+        RwLock::new(value)
+    }
+}
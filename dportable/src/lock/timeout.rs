@@ -0,0 +1,114 @@
+//! Async lock acquisition with timeout.
+
+use std::{fmt::Display, future::Future, pin::pin};
+
+use futures::future::{select, Either};
+
+use super::{
+    AsyncMutex, AsyncMutexGuard, AsyncRwLock, AsyncRwLockReadGuard, AsyncRwLockWriteGuard,
+};
+use crate::time::Timeout;
+
+/// Lock could not be acquired before timeout error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LockTimedOut {}
+
+impl Display for LockTimedOut {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "lock acquisition timed out")
+    }
+}
+
+impl std::error::Error for LockTimedOut {}
+
+async fn with_timeout<F>(future: F, timeout: Timeout) -> Result<F::Output, LockTimedOut>
+where
+    F: Future,
+{
+    match select(pin!(future), timeout).await {
+        Either::Left((output, _)) => Ok(output),
+        Either::Right(_) => Err(LockTimedOut {}),
+    }
+}
+
+/// Timed acquisition of [AsyncMutex].
+pub trait AsyncMutexExt<T>
+where
+    T: ?Sized,
+{
+    /// Lock mutex, failing with [LockTimedOut] if `timeout` occurs first.
+    ///
+    /// Accepts either [Duration](std::time::Duration) or [Timeout].
+    fn lock_timeout<'a>(
+        &'a self,
+        timeout: impl Into<Timeout>,
+    ) -> impl Future<Output = Result<AsyncMutexGuard<'a, T>, LockTimedOut>>
+    where
+        T: 'a;
+}
+
+impl<T> AsyncMutexExt<T> for AsyncMutex<T>
+where
+    T: ?Sized,
+{
+    fn lock_timeout<'a>(
+        &'a self,
+        timeout: impl Into<Timeout>,
+    ) -> impl Future<Output = Result<AsyncMutexGuard<'a, T>, LockTimedOut>>
+    where
+        T: 'a,
+    {
+        with_timeout(self.lock(), timeout.into())
+    }
+}
+
+/// Timed acquisition of [AsyncRwLock].
+pub trait AsyncRwLockExt<T>
+where
+    T: ?Sized,
+{
+    /// Lock with shared read access, failing with [LockTimedOut] if `timeout` occurs first.
+    ///
+    /// Accepts either [Duration](std::time::Duration) or [Timeout].
+    fn read_timeout<'a>(
+        &'a self,
+        timeout: impl Into<Timeout>,
+    ) -> impl Future<Output = Result<AsyncRwLockReadGuard<'a, T>, LockTimedOut>>
+    where
+        T: 'a;
+
+    /// Lock with exclusive write access, failing with [LockTimedOut] if `timeout` occurs first.
+    ///
+    /// Accepts either [Duration](std::time::Duration) or [Timeout].
+    fn write_timeout<'a>(
+        &'a self,
+        timeout: impl Into<Timeout>,
+    ) -> impl Future<Output = Result<AsyncRwLockWriteGuard<'a, T>, LockTimedOut>>
+    where
+        T: 'a;
+}
+
+impl<T> AsyncRwLockExt<T> for AsyncRwLock<T>
+where
+    T: ?Sized,
+{
+    fn read_timeout<'a>(
+        &'a self,
+        timeout: impl Into<Timeout>,
+    ) -> impl Future<Output = Result<AsyncRwLockReadGuard<'a, T>, LockTimedOut>>
+    where
+        T: 'a,
+    {
+        with_timeout(self.read(), timeout.into())
+    }
+
+    fn write_timeout<'a>(
+        &'a self,
+        timeout: impl Into<Timeout>,
+    ) -> impl Future<Output = Result<AsyncRwLockWriteGuard<'a, T>, LockTimedOut>>
+    where
+        T: 'a,
+    {
+        with_timeout(self.write(), timeout.into())
+    }
+}
@@ -1,20 +1,30 @@
 use std::{
-    cell::RefCell,
+    cell::{Ref, RefCell, RefMut},
+    collections::HashSet,
     fmt::Display,
     ops::{Deref, DerefMut},
+    panic::Location,
+    time::Duration,
 };
 
+use crate::time::Instant;
+
+use super::diagnostics::{contended, Holder};
+
 #[derive(Debug)]
 pub struct Mutex<T>(pub RefCell<T>)
 where
     T: ?Sized;
 
 #[derive(Debug)]
-pub struct MutexGuard<'a, T>(std::cell::RefMut<'a, T>)
+pub struct MutexGuard<'a, T>(RefMut<'a, T>, #[allow(dead_code)] Holder)
 where
     T: ?Sized + 'a;
 
-impl<T> Deref for MutexGuard<'_, T> {
+impl<T> Deref for MutexGuard<'_, T>
+where
+    T: ?Sized,
+{
     type Target = T;
 
     fn deref(&self) -> &Self::Target {
@@ -22,7 +32,10 @@ impl<T> Deref for MutexGuard<'_, T> {
     }
 }
 
-impl<T> DerefMut for MutexGuard<'_, T> {
+impl<T> DerefMut for MutexGuard<'_, T>
+where
+    T: ?Sized,
+{
     fn deref_mut(&mut self) -> &mut Self::Target {
         &mut self.0
     }
@@ -30,10 +43,92 @@ impl<T> DerefMut for MutexGuard<'_, T> {
 
 impl<T> Display for MutexGuard<'_, T>
 where
-    T: Display,
+    T: ?Sized + Display,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(&*self.0, f)
+    }
+}
+
+impl<'a, T> MutexGuard<'a, T>
+where
+    T: ?Sized,
+{
+    pub fn map<U, F>(s: Self, f: F) -> MappedMutexGuard<'a, U>
+    where
+        U: ?Sized,
+        F: FnOnce(&mut T) -> &mut U,
+    {
+        MappedMutexGuard(RefMut::map(s.0, f), s.1)
+    }
+
+    pub fn try_map<U, F>(s: Self, f: F) -> Result<MappedMutexGuard<'a, U>, Self>
+    where
+        U: ?Sized,
+        F: FnOnce(&mut T) -> Option<&mut U>,
+    {
+        match RefMut::filter_map(s.0, f) {
+            Ok(value) => Ok(MappedMutexGuard(value, s.1)),
+            Err(value) => Err(MutexGuard(value, s.1)),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct MappedMutexGuard<'a, T>(RefMut<'a, T>, #[allow(dead_code)] Holder)
+where
+    T: ?Sized + 'a;
+
+impl<'a, T> MappedMutexGuard<'a, T>
+where
+    T: ?Sized,
+{
+    pub fn map<U, F>(s: Self, f: F) -> MappedMutexGuard<'a, U>
+    where
+        U: ?Sized,
+        F: FnOnce(&mut T) -> &mut U,
+    {
+        MappedMutexGuard(RefMut::map(s.0, f), s.1)
+    }
+
+    pub fn try_map<U, F>(s: Self, f: F) -> Result<MappedMutexGuard<'a, U>, Self>
+    where
+        U: ?Sized,
+        F: FnOnce(&mut T) -> Option<&mut U>,
+    {
+        match RefMut::filter_map(s.0, f) {
+            Ok(value) => Ok(MappedMutexGuard(value, s.1)),
+            Err(value) => Err(MappedMutexGuard(value, s.1)),
+        }
+    }
+}
+
+impl<T> Deref for MappedMutexGuard<'_, T>
+where
+    T: ?Sized,
+{
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for MappedMutexGuard<'_, T>
+where
+    T: ?Sized,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<T> Display for MappedMutexGuard<'_, T>
+where
+    T: ?Sized + Display,
 {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        Display::fmt(&self.0, f)
+        Display::fmt(&*self.0, f)
     }
 }
 
@@ -42,12 +137,66 @@ impl<T> Mutex<T> {
         Mutex(RefCell::new(value))
     }
 
+    pub fn into_inner(self) -> T {
+        self.0.into_inner()
+    }
+}
+
+impl<T> Mutex<T>
+where
+    T: ?Sized,
+{
+    #[track_caller]
     pub fn lock(&self) -> MutexGuard<'_, T> {
-        MutexGuard(self.0.borrow_mut())
+        let location = Location::caller();
+        self.try_lock()
+            .unwrap_or_else(|| contended("Mutex", self.key(), location))
     }
 
+    #[track_caller]
     pub fn try_lock(&self) -> Option<MutexGuard<'_, T>> {
-        self.0.try_borrow_mut().ok().map(MutexGuard)
+        let location = Location::caller();
+        self.0
+            .try_borrow_mut()
+            .ok()
+            .map(|value| MutexGuard(value, Holder::exclusive(self.key(), location)))
+    }
+
+    #[track_caller]
+    pub fn try_lock_for(&self, _timeout: Duration) -> Option<MutexGuard<'_, T>> {
+        self.try_lock()
+    }
+
+    #[track_caller]
+    pub fn try_lock_until(&self, _timeout: Instant) -> Option<MutexGuard<'_, T>> {
+        self.try_lock()
+    }
+
+    pub fn is_locked(&self) -> bool {
+        self.0.try_borrow_mut().is_err()
+    }
+
+    pub fn get_mut(&mut self) -> &mut T {
+        self.0.get_mut()
+    }
+
+    fn key(&self) -> usize {
+        self as *const Self as *const () as usize
+    }
+}
+
+impl<T> Default for Mutex<T>
+where
+    T: Default,
+{
+    fn default() -> Self {
+        Mutex::new(T::default())
+    }
+}
+
+impl<T> From<T> for Mutex<T> {
+    fn from(value: T) -> Self {
+        Mutex::new(value)
     }
 }
 
@@ -57,20 +206,23 @@ where
     T: ?Sized;
 
 #[derive(Debug)]
-pub struct RwLockReadGuard<'a, T>(std::cell::Ref<'a, T>)
+pub struct RwLockReadGuard<'a, T>(Ref<'a, T>, #[allow(dead_code)] Holder)
 where
     T: ?Sized + 'a;
 
 impl<T> Display for RwLockReadGuard<'_, T>
 where
-    T: Display,
+    T: ?Sized + Display,
 {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        Display::fmt(&self.0, f)
+        Display::fmt(&*self.0, f)
     }
 }
 
-impl<T> Deref for RwLockReadGuard<'_, T> {
+impl<T> Deref for RwLockReadGuard<'_, T>
+where
+    T: ?Sized,
+{
     type Target = T;
 
     fn deref(&self) -> &Self::Target {
@@ -78,12 +230,63 @@ impl<T> Deref for RwLockReadGuard<'_, T> {
     }
 }
 
+impl<'a, T> RwLockReadGuard<'a, T>
+where
+    T: ?Sized,
+{
+    pub fn map<U, F>(s: Self, f: F) -> MappedRwLockReadGuard<'a, U>
+    where
+        U: ?Sized,
+        F: FnOnce(&T) -> &U,
+    {
+        MappedRwLockReadGuard(Ref::map(s.0, f), s.1)
+    }
+
+    pub fn try_map<U, F>(s: Self, f: F) -> Result<MappedRwLockReadGuard<'a, U>, Self>
+    where
+        U: ?Sized,
+        F: FnOnce(&T) -> Option<&U>,
+    {
+        match Ref::filter_map(s.0, f) {
+            Ok(value) => Ok(MappedRwLockReadGuard(value, s.1)),
+            Err(value) => Err(RwLockReadGuard(value, s.1)),
+        }
+    }
+}
+
 #[derive(Debug)]
-pub struct RwLockWriteGuard<'a, T>(std::cell::RefMut<'a, T>)
+pub struct MappedRwLockReadGuard<'a, T>(Ref<'a, T>, #[allow(dead_code)] Holder)
 where
     T: ?Sized + 'a;
 
-impl<T> Deref for RwLockWriteGuard<'_, T> {
+impl<'a, T> MappedRwLockReadGuard<'a, T>
+where
+    T: ?Sized,
+{
+    pub fn map<U, F>(s: Self, f: F) -> MappedRwLockReadGuard<'a, U>
+    where
+        U: ?Sized,
+        F: FnOnce(&T) -> &U,
+    {
+        MappedRwLockReadGuard(Ref::map(s.0, f), s.1)
+    }
+
+    pub fn try_map<U, F>(s: Self, f: F) -> Result<MappedRwLockReadGuard<'a, U>, Self>
+    where
+        U: ?Sized,
+        F: FnOnce(&T) -> Option<&U>,
+    {
+        match Ref::filter_map(s.0, f) {
+            Ok(value) => Ok(MappedRwLockReadGuard(value, s.1)),
+            Err(value) => Err(MappedRwLockReadGuard(value, s.1)),
+        }
+    }
+}
+
+impl<T> Deref for MappedRwLockReadGuard<'_, T>
+where
+    T: ?Sized,
+{
     type Target = T;
 
     fn deref(&self) -> &Self::Target {
@@ -91,7 +294,128 @@ impl<T> Deref for RwLockWriteGuard<'_, T> {
     }
 }
 
-impl<T> DerefMut for RwLockWriteGuard<'_, T> {
+impl<T> Display for MappedRwLockReadGuard<'_, T>
+where
+    T: ?Sized + Display,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(&*self.0, f)
+    }
+}
+
+thread_local! {
+    /// Keys of locks with outstanding upgradable read guard.
+    static UPGRADABLE: RefCell<HashSet<usize>> = RefCell::new(HashSet::new());
+}
+
+/// Marks lock as having outstanding upgradable read guard until dropped.
+#[derive(Debug)]
+struct Upgradable(usize);
+
+impl Upgradable {
+    fn try_new(key: usize) -> Option<Self> {
+        UPGRADABLE
+            .with_borrow_mut(|upgradable| upgradable.insert(key))
+            .then_some(Upgradable(key))
+    }
+}
+
+impl Drop for Upgradable {
+    fn drop(&mut self) {
+        UPGRADABLE.with_borrow_mut(|upgradable| upgradable.remove(&self.0));
+    }
+}
+
+#[derive(Debug)]
+pub struct RwLockUpgradableReadGuard<'a, T>(
+    Ref<'a, T>,
+    #[allow(dead_code)] Holder,
+    &'a RwLock<T>,
+    #[allow(dead_code)] Upgradable,
+)
+where
+    T: ?Sized + 'a;
+
+impl<'a, T> RwLockUpgradableReadGuard<'a, T>
+where
+    T: ?Sized,
+{
+    #[track_caller]
+    pub fn upgrade(s: Self) -> RwLockWriteGuard<'a, T> {
+        let location = Location::caller();
+        let lock = s.2;
+        Self::try_upgrade(s).unwrap_or_else(|_| contended("RwLock", lock.key(), location))
+    }
+
+    #[track_caller]
+    pub fn try_upgrade(s: Self) -> Result<RwLockWriteGuard<'a, T>, Self> {
+        let location = Location::caller();
+        let RwLockUpgradableReadGuard(value, holder, lock, upgradable) = s;
+        drop(value);
+        match lock.0.try_borrow_mut() {
+            Ok(value) => {
+                drop(holder);
+                Ok(RwLockWriteGuard(
+                    value,
+                    Holder::exclusive(lock.key(), location),
+                    lock,
+                ))
+            }
+            // Only other readers can be holding the lock, so it can be borrowed again.
+            Err(_) => Err(RwLockUpgradableReadGuard(
+                lock.0.borrow(),
+                holder,
+                lock,
+                upgradable,
+            )),
+        }
+    }
+
+    pub fn downgrade(s: Self) -> RwLockReadGuard<'a, T> {
+        RwLockReadGuard(s.0, s.1)
+    }
+}
+
+impl<T> Deref for RwLockUpgradableReadGuard<'_, T>
+where
+    T: ?Sized,
+{
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> Display for RwLockUpgradableReadGuard<'_, T>
+where
+    T: ?Sized + Display,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(&*self.0, f)
+    }
+}
+
+#[derive(Debug)]
+pub struct RwLockWriteGuard<'a, T>(RefMut<'a, T>, #[allow(dead_code)] Holder, &'a RwLock<T>)
+where
+    T: ?Sized + 'a;
+
+impl<T> Deref for RwLockWriteGuard<'_, T>
+where
+    T: ?Sized,
+{
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for RwLockWriteGuard<'_, T>
+where
+    T: ?Sized,
+{
     fn deref_mut(&mut self) -> &mut Self::Target {
         &mut self.0
     }
@@ -99,10 +423,101 @@ impl<T> DerefMut for RwLockWriteGuard<'_, T> {
 
 impl<T> Display for RwLockWriteGuard<'_, T>
 where
-    T: Display,
+    T: ?Sized + Display,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(&*self.0, f)
+    }
+}
+
+impl<'a, T> RwLockWriteGuard<'a, T>
+where
+    T: ?Sized,
+{
+    pub fn map<U, F>(s: Self, f: F) -> MappedRwLockWriteGuard<'a, U>
+    where
+        U: ?Sized,
+        F: FnOnce(&mut T) -> &mut U,
+    {
+        MappedRwLockWriteGuard(RefMut::map(s.0, f), s.1)
+    }
+
+    pub fn try_map<U, F>(s: Self, f: F) -> Result<MappedRwLockWriteGuard<'a, U>, Self>
+    where
+        U: ?Sized,
+        F: FnOnce(&mut T) -> Option<&mut U>,
+    {
+        match RefMut::filter_map(s.0, f) {
+            Ok(value) => Ok(MappedRwLockWriteGuard(value, s.1)),
+            Err(value) => Err(RwLockWriteGuard(value, s.1, s.2)),
+        }
+    }
+
+    #[track_caller]
+    pub fn downgrade(s: Self) -> RwLockReadGuard<'a, T> {
+        let location = Location::caller();
+        let RwLockWriteGuard(value, holder, lock) = s;
+        drop(value);
+        drop(holder);
+        RwLockReadGuard(lock.0.borrow(), Holder::shared(lock.key(), location))
+    }
+}
+
+#[derive(Debug)]
+pub struct MappedRwLockWriteGuard<'a, T>(RefMut<'a, T>, #[allow(dead_code)] Holder)
+where
+    T: ?Sized + 'a;
+
+impl<'a, T> MappedRwLockWriteGuard<'a, T>
+where
+    T: ?Sized,
+{
+    pub fn map<U, F>(s: Self, f: F) -> MappedRwLockWriteGuard<'a, U>
+    where
+        U: ?Sized,
+        F: FnOnce(&mut T) -> &mut U,
+    {
+        MappedRwLockWriteGuard(RefMut::map(s.0, f), s.1)
+    }
+
+    pub fn try_map<U, F>(s: Self, f: F) -> Result<MappedRwLockWriteGuard<'a, U>, Self>
+    where
+        U: ?Sized,
+        F: FnOnce(&mut T) -> Option<&mut U>,
+    {
+        match RefMut::filter_map(s.0, f) {
+            Ok(value) => Ok(MappedRwLockWriteGuard(value, s.1)),
+            Err(value) => Err(MappedRwLockWriteGuard(value, s.1)),
+        }
+    }
+}
+
+impl<T> Deref for MappedRwLockWriteGuard<'_, T>
+where
+    T: ?Sized,
+{
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for MappedRwLockWriteGuard<'_, T>
+where
+    T: ?Sized,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<T> Display for MappedRwLockWriteGuard<'_, T>
+where
+    T: ?Sized + Display,
 {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        Display::fmt(&self.0, f)
+        Display::fmt(&*self.0, f)
     }
 }
 
@@ -111,19 +526,115 @@ impl<T> RwLock<T> {
         RwLock(RefCell::new(value))
     }
 
+    pub fn into_inner(self) -> T {
+        self.0.into_inner()
+    }
+}
+
+impl<T> RwLock<T>
+where
+    T: ?Sized,
+{
+    #[track_caller]
     pub fn read(&self) -> RwLockReadGuard<'_, T> {
-        RwLockReadGuard(self.0.borrow())
+        let location = Location::caller();
+        self.try_read()
+            .unwrap_or_else(|| contended("RwLock", self.key(), location))
     }
 
+    #[track_caller]
     pub fn try_read(&self) -> Option<RwLockReadGuard<'_, T>> {
-        self.0.try_borrow().ok().map(RwLockReadGuard)
+        let location = Location::caller();
+        self.0
+            .try_borrow()
+            .ok()
+            .map(|value| RwLockReadGuard(value, Holder::shared(self.key(), location)))
     }
 
+    #[track_caller]
+    pub fn try_read_for(&self, _timeout: Duration) -> Option<RwLockReadGuard<'_, T>> {
+        self.try_read()
+    }
+
+    #[track_caller]
+    pub fn try_read_until(&self, _timeout: Instant) -> Option<RwLockReadGuard<'_, T>> {
+        self.try_read()
+    }
+
+    #[track_caller]
     pub fn write(&self) -> RwLockWriteGuard<'_, T> {
-        RwLockWriteGuard(self.0.borrow_mut())
+        let location = Location::caller();
+        self.try_write()
+            .unwrap_or_else(|| contended("RwLock", self.key(), location))
     }
 
+    #[track_caller]
     pub fn try_write(&self) -> Option<RwLockWriteGuard<'_, T>> {
-        self.0.try_borrow_mut().ok().map(RwLockWriteGuard)
+        let location = Location::caller();
+        self.0
+            .try_borrow_mut()
+            .ok()
+            .map(|value| RwLockWriteGuard(value, Holder::exclusive(self.key(), location), self))
+    }
+
+    #[track_caller]
+    pub fn try_write_for(&self, _timeout: Duration) -> Option<RwLockWriteGuard<'_, T>> {
+        self.try_write()
+    }
+
+    #[track_caller]
+    pub fn try_write_until(&self, _timeout: Instant) -> Option<RwLockWriteGuard<'_, T>> {
+        self.try_write()
+    }
+
+    #[track_caller]
+    pub fn upgradable_read(&self) -> RwLockUpgradableReadGuard<'_, T> {
+        let location = Location::caller();
+        self.try_upgradable_read()
+            .unwrap_or_else(|| contended("RwLock", self.key(), location))
+    }
+
+    #[track_caller]
+    pub fn try_upgradable_read(&self) -> Option<RwLockUpgradableReadGuard<'_, T>> {
+        let location = Location::caller();
+        let value = self.0.try_borrow().ok()?;
+        let upgradable = Upgradable::try_new(self.key())?;
+        Some(RwLockUpgradableReadGuard(
+            value,
+            Holder::upgradable(self.key(), location),
+            self,
+            upgradable,
+        ))
+    }
+
+    pub fn is_locked(&self) -> bool {
+        self.0.try_borrow_mut().is_err()
+    }
+
+    pub fn is_locked_exclusive(&self) -> bool {
+        self.0.try_borrow().is_err()
+    }
+
+    pub fn get_mut(&mut self) -> &mut T {
+        self.0.get_mut()
+    }
+
+    fn key(&self) -> usize {
+        self as *const Self as *const () as usize
+    }
+}
+
+impl<T> Default for RwLock<T>
+where
+    T: Default,
+{
+    fn default() -> Self {
+        RwLock::new(T::default())
+    }
+}
+
+impl<T> From<T> for RwLock<T> {
+    fn from(value: T) -> Self {
+        RwLock::new(value)
     }
 }
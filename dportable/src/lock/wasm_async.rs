@@ -0,0 +1,412 @@
+use std::{
+    cell::UnsafeCell,
+    fmt::{Debug, Display},
+    ops::{Deref, DerefMut},
+    sync::Arc,
+};
+
+use crate::sync::Semaphore;
+
+async fn acquire(semaphore: &Semaphore, permits: usize) {
+    if semaphore.acquire_permits(permits).await.is_err() {
+        unreachable!("lock semaphores are never closed")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TryLockError(());
+
+impl Display for TryLockError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "operation would block")
+    }
+}
+
+impl std::error::Error for TryLockError {}
+
+pub struct AsyncMutex<T>
+where
+    T: ?Sized,
+{
+    semaphore: Semaphore,
+    value: UnsafeCell<T>,
+}
+
+impl<T> AsyncMutex<T> {
+    pub fn new(value: T) -> Self {
+        AsyncMutex {
+            semaphore: Semaphore::new(1),
+            value: UnsafeCell::new(value),
+        }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.value.into_inner()
+    }
+}
+
+impl<T> AsyncMutex<T>
+where
+    T: ?Sized,
+{
+    pub async fn lock(&self) -> AsyncMutexGuard<'_, T> {
+        acquire(&self.semaphore, 1).await;
+        AsyncMutexGuard { mutex: self }
+    }
+
+    pub fn try_lock(&self) -> Result<AsyncMutexGuard<'_, T>, TryLockError> {
+        if self.semaphore.try_acquire_permits(1).is_ok() {
+            Ok(AsyncMutexGuard { mutex: self })
+        } else {
+            Err(TryLockError(()))
+        }
+    }
+
+    pub async fn lock_owned(self: Arc<Self>) -> OwnedAsyncMutexGuard<T> {
+        acquire(&self.semaphore, 1).await;
+        OwnedAsyncMutexGuard { mutex: self }
+    }
+
+    pub fn try_lock_owned(self: Arc<Self>) -> Result<OwnedAsyncMutexGuard<T>, TryLockError> {
+        if self.semaphore.try_acquire_permits(1).is_ok() {
+            Ok(OwnedAsyncMutexGuard { mutex: self })
+        } else {
+            Err(TryLockError(()))
+        }
+    }
+
+    pub fn get_mut(&mut self) -> &mut T {
+        self.value.get_mut()
+    }
+}
+
+impl<T> Default for AsyncMutex<T>
+where
+    T: Default,
+{
+    fn default() -> Self {
+        AsyncMutex::new(T::default())
+    }
+}
+
+impl<T> From<T> for AsyncMutex<T> {
+    fn from(value: T) -> Self {
+        AsyncMutex::new(value)
+    }
+}
+
+impl<T> Debug for AsyncMutex<T>
+where
+    T: ?Sized + Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut debug = f.debug_struct("AsyncMutex");
+        match self.try_lock() {
+            Ok(guard) => debug.field("data", &&*guard),
+            Err(_) => debug.field("data", &format_args!("<locked>")),
+        };
+        debug.finish()
+    }
+}
+
+pub struct AsyncMutexGuard<'a, T>
+where
+    T: ?Sized,
+{
+    mutex: &'a AsyncMutex<T>,
+}
+
+impl<T> Deref for AsyncMutexGuard<'_, T>
+where
+    T: ?Sized,
+{
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        // SAFETY: holding the guard means holding the only semaphore permit.
+        unsafe { &*self.mutex.value.get() }
+    }
+}
+
+impl<T> DerefMut for AsyncMutexGuard<'_, T>
+where
+    T: ?Sized,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        // SAFETY: holding the guard means holding the only semaphore permit.
+        unsafe { &mut *self.mutex.value.get() }
+    }
+}
+
+impl<T> Drop for AsyncMutexGuard<'_, T>
+where
+    T: ?Sized,
+{
+    fn drop(&mut self) {
+        self.mutex.semaphore.release(1);
+    }
+}
+
+impl<T> Debug for AsyncMutexGuard<'_, T>
+where
+    T: ?Sized + Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Debug::fmt(&**self, f)
+    }
+}
+
+impl<T> Display for AsyncMutexGuard<'_, T>
+where
+    T: ?Sized + Display,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(&**self, f)
+    }
+}
+
+pub struct OwnedAsyncMutexGuard<T>
+where
+    T: ?Sized,
+{
+    mutex: Arc<AsyncMutex<T>>,
+}
+
+impl<T> Deref for OwnedAsyncMutexGuard<T>
+where
+    T: ?Sized,
+{
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        // SAFETY: holding the guard means holding the only semaphore permit.
+        unsafe { &*self.mutex.value.get() }
+    }
+}
+
+impl<T> DerefMut for OwnedAsyncMutexGuard<T>
+where
+    T: ?Sized,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        // SAFETY: holding the guard means holding the only semaphore permit.
+        unsafe { &mut *self.mutex.value.get() }
+    }
+}
+
+impl<T> Drop for OwnedAsyncMutexGuard<T>
+where
+    T: ?Sized,
+{
+    fn drop(&mut self) {
+        self.mutex.semaphore.release(1);
+    }
+}
+
+impl<T> Debug for OwnedAsyncMutexGuard<T>
+where
+    T: ?Sized + Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Debug::fmt(&**self, f)
+    }
+}
+
+impl<T> Display for OwnedAsyncMutexGuard<T>
+where
+    T: ?Sized + Display,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(&**self, f)
+    }
+}
+
+/// Number of permits a writer needs, i.e. maximum number of concurrent readers.
+const MAX_READS: usize = usize::MAX >> 3;
+
+pub struct AsyncRwLock<T>
+where
+    T: ?Sized,
+{
+    semaphore: Semaphore,
+    value: UnsafeCell<T>,
+}
+
+impl<T> AsyncRwLock<T> {
+    pub fn new(value: T) -> Self {
+        AsyncRwLock {
+            semaphore: Semaphore::new(MAX_READS),
+            value: UnsafeCell::new(value),
+        }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.value.into_inner()
+    }
+}
+
+impl<T> AsyncRwLock<T>
+where
+    T: ?Sized,
+{
+    pub async fn read(&self) -> AsyncRwLockReadGuard<'_, T> {
+        acquire(&self.semaphore, 1).await;
+        AsyncRwLockReadGuard { lock: self }
+    }
+
+    pub fn try_read(&self) -> Result<AsyncRwLockReadGuard<'_, T>, TryLockError> {
+        if self.semaphore.try_acquire_permits(1).is_ok() {
+            Ok(AsyncRwLockReadGuard { lock: self })
+        } else {
+            Err(TryLockError(()))
+        }
+    }
+
+    pub async fn write(&self) -> AsyncRwLockWriteGuard<'_, T> {
+        acquire(&self.semaphore, MAX_READS).await;
+        AsyncRwLockWriteGuard { lock: self }
+    }
+
+    pub fn try_write(&self) -> Result<AsyncRwLockWriteGuard<'_, T>, TryLockError> {
+        if self.semaphore.try_acquire_permits(MAX_READS).is_ok() {
+            Ok(AsyncRwLockWriteGuard { lock: self })
+        } else {
+            Err(TryLockError(()))
+        }
+    }
+
+    pub fn get_mut(&mut self) -> &mut T {
+        self.value.get_mut()
+    }
+}
+
+impl<T> Default for AsyncRwLock<T>
+where
+    T: Default,
+{
+    fn default() -> Self {
+        AsyncRwLock::new(T::default())
+    }
+}
+
+impl<T> From<T> for AsyncRwLock<T> {
+    fn from(value: T) -> Self {
+        AsyncRwLock::new(value)
+    }
+}
+
+impl<T> Debug for AsyncRwLock<T>
+where
+    T: ?Sized + Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut debug = f.debug_struct("AsyncRwLock");
+        match self.try_read() {
+            Ok(guard) => debug.field("data", &&*guard),
+            Err(_) => debug.field("data", &format_args!("<locked>")),
+        };
+        debug.finish()
+    }
+}
+
+pub struct AsyncRwLockReadGuard<'a, T>
+where
+    T: ?Sized,
+{
+    lock: &'a AsyncRwLock<T>,
+}
+
+impl<T> Deref for AsyncRwLockReadGuard<'_, T>
+where
+    T: ?Sized,
+{
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        // SAFETY: writers can't hold all permits while a reader holds one.
+        unsafe { &*self.lock.value.get() }
+    }
+}
+
+impl<T> Drop for AsyncRwLockReadGuard<'_, T>
+where
+    T: ?Sized,
+{
+    fn drop(&mut self) {
+        self.lock.semaphore.release(1);
+    }
+}
+
+impl<T> Debug for AsyncRwLockReadGuard<'_, T>
+where
+    T: ?Sized + Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Debug::fmt(&**self, f)
+    }
+}
+
+impl<T> Display for AsyncRwLockReadGuard<'_, T>
+where
+    T: ?Sized + Display,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(&**self, f)
+    }
+}
+
+pub struct AsyncRwLockWriteGuard<'a, T>
+where
+    T: ?Sized,
+{
+    lock: &'a AsyncRwLock<T>,
+}
+
+impl<T> Deref for AsyncRwLockWriteGuard<'_, T>
+where
+    T: ?Sized,
+{
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        // SAFETY: holding the guard means holding all semaphore permits.
+        unsafe { &*self.lock.value.get() }
+    }
+}
+
+impl<T> DerefMut for AsyncRwLockWriteGuard<'_, T>
+where
+    T: ?Sized,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        // SAFETY: holding the guard means holding all semaphore permits.
+        unsafe { &mut *self.lock.value.get() }
+    }
+}
+
+impl<T> Drop for AsyncRwLockWriteGuard<'_, T>
+where
+    T: ?Sized,
+{
+    fn drop(&mut self) {
+        self.lock.semaphore.release(MAX_READS);
+    }
+}
+
+impl<T> Debug for AsyncRwLockWriteGuard<'_, T>
+where
+    T: ?Sized + Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Debug::fmt(&**self, f)
+    }
+}
+
+impl<T> Display for AsyncRwLockWriteGuard<'_, T>
+where
+    T: ?Sized + Display,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(&**self, f)
+    }
+}
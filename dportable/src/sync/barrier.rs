@@ -0,0 +1,97 @@
+//! Async barrier.
+
+use crate::{value::mutable::Notifier, Mutex};
+
+/// Barrier making tasks wait until all of them reach it.
+///
+/// Barrier is reusable - once all tasks are released it can be waited on again.
+#[derive(Debug)]
+pub struct Barrier {
+    participants: usize,
+    state: Mutex<BarrierState>,
+}
+
+#[derive(Debug)]
+struct BarrierState {
+    arrived: usize,
+    notifier: Notifier,
+}
+
+impl Barrier {
+    /// Create new barrier for `participants` tasks.
+    ///
+    /// Barrier created for `0` participants behaves like one created for `1`.
+    pub fn new(participants: usize) -> Self {
+        Barrier {
+            participants: participants.max(1),
+            state: Mutex::new(BarrierState {
+                arrived: 0,
+                notifier: Notifier::new(),
+            }),
+        }
+    }
+
+    /// Wait until all participants reach the barrier.
+    ///
+    /// Exactly one of the released tasks (the last one to arrive) is the leader.
+    pub async fn wait(&self) -> BarrierWaitResult {
+        let notified = {
+            let mut state = self.state.lock();
+            state.arrived += 1;
+            if state.arrived == self.participants {
+                state.arrived = 0;
+                state.notifier.notify();
+                state.notifier.reset();
+                return BarrierWaitResult(true);
+            }
+            state.notifier.notified()
+        };
+        notified.await;
+        BarrierWaitResult(false)
+    }
+}
+
+/// Result of [Barrier::wait].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BarrierWaitResult(bool);
+
+impl BarrierWaitResult {
+    /// Returns [true] if this task is the leader.
+    pub fn is_leader(&self) -> bool {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::spawn;
+    use crate::test::{dtest, dtest_configure};
+
+    use super::Barrier;
+
+    dtest_configure!();
+
+    #[dtest]
+    async fn test_barrier() {
+        let barrier = Arc::new(Barrier::new(3));
+        for _ in 0..2 {
+            let join_handles: Vec<_> = (0..3)
+                .map(|_| {
+                    let barrier = barrier.clone();
+                    spawn(async move { barrier.wait().await.is_leader() })
+                })
+                .collect();
+            let mut leaders = 0;
+            for join_handle in join_handles {
+                if join_handle.await.unwrap() {
+                    leaders += 1;
+                }
+            }
+            assert_eq!(1, leaders);
+        }
+
+        assert!(Barrier::new(0).wait().await.is_leader());
+    }
+}
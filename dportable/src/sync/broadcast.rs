@@ -0,0 +1,406 @@
+//! Multi-producer, multi-consumer broadcast channel.
+//!
+//! Every value sent is received by every receiver subscribed at the time.
+//! Channel keeps at most `capacity` most recent values, receivers falling further behind
+//! skip the oldest values and are notified about it with [RecvError::Lagged].
+
+use std::{
+    fmt::{Debug, Display},
+    future::poll_fn,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll, Waker},
+};
+
+use futures::{future::ready, Stream, StreamExt};
+
+use crate::{Mutex, RwLock};
+
+/// Error returned by [Sender::send] when there are no receivers.
+#[derive(PartialEq, Eq, Clone, Copy)]
+pub struct SendError<T>(pub T);
+
+impl<T> Debug for SendError<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SendError").finish_non_exhaustive()
+    }
+}
+
+impl<T> Display for SendError<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "channel closed")
+    }
+}
+
+impl<T> std::error::Error for SendError<T> {}
+
+/// Error returned by [Receiver::recv].
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum RecvError {
+    /// All senders were dropped and there are no more values to receive.
+    Closed,
+
+    /// Receiver fell behind, given number of oldest values was skipped.
+    Lagged(u64),
+}
+
+impl Display for RecvError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RecvError::Closed => write!(f, "channel closed"),
+            RecvError::Lagged(skipped) => write!(f, "channel lagged by {skipped}"),
+        }
+    }
+}
+
+impl std::error::Error for RecvError {}
+
+/// Error returned by [Receiver::try_recv].
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum TryRecvError {
+    /// There are no values to receive at the moment.
+    Empty,
+
+    /// All senders were dropped and there are no more values to receive.
+    Closed,
+
+    /// Receiver fell behind, given number of oldest values was skipped.
+    Lagged(u64),
+}
+
+impl Display for TryRecvError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TryRecvError::Empty => write!(f, "channel empty"),
+            TryRecvError::Closed => write!(f, "channel closed"),
+            TryRecvError::Lagged(skipped) => write!(f, "channel lagged by {skipped}"),
+        }
+    }
+}
+
+impl std::error::Error for TryRecvError {}
+
+/// Item of [Receiver]'s stream, signalling given number of values was skipped.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Lagged(pub u64);
+
+impl Display for Lagged {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "channel lagged by {}", self.0)
+    }
+}
+
+impl std::error::Error for Lagged {}
+
+#[derive(Debug)]
+struct Shared<T> {
+    buffer: RwLock<Buffer<T>>,
+    wakers: Mutex<Vec<Waker>>,
+}
+
+#[derive(Debug)]
+struct Buffer<T> {
+    slots: Box<[Option<T>]>,
+    /// Position of next value to be sent.
+    tail: u64,
+    senders: usize,
+    receivers: usize,
+}
+
+impl<T> Buffer<T> {
+    fn capacity(&self) -> u64 {
+        self.slots.len() as u64
+    }
+
+    fn slot(&self, position: u64) -> &Option<T> {
+        &self.slots[(position % self.capacity()) as usize]
+    }
+}
+
+impl<T> Buffer<T>
+where
+    T: Clone,
+{
+    /// Take value at position `next`, advancing it.
+    fn take(&self, next: &mut u64) -> Result<T, TryRecvError> {
+        let oldest = self.tail.saturating_sub(self.capacity());
+        if *next < oldest {
+            let skipped = oldest - *next;
+            *next = oldest;
+            Err(TryRecvError::Lagged(skipped))
+        } else if *next < self.tail {
+            let value = self
+                .slot(*next)
+                .clone()
+                .expect("sent value present in buffer");
+            *next += 1;
+            Ok(value)
+        } else if self.senders == 0 {
+            Err(TryRecvError::Closed)
+        } else {
+            Err(TryRecvError::Empty)
+        }
+    }
+}
+
+impl<T> Shared<T> {
+    fn wake_all(&self) {
+        let wakers = std::mem::take(&mut *self.wakers.lock());
+        for waker in wakers {
+            waker.wake();
+        }
+    }
+}
+
+/// Create new broadcast channel keeping at most `capacity` values.
+///
+/// # Panics
+///
+/// Panics if `capacity` is `0`.
+#[track_caller]
+pub fn channel<T>(capacity: usize) -> (Sender<T>, Receiver<T>) {
+    assert!(capacity > 0, "broadcast channel requires capacity > 0");
+    let shared = Arc::new(Shared {
+        buffer: RwLock::new(Buffer {
+            slots: (0..capacity).map(|_| None).collect(),
+            tail: 0,
+            senders: 1,
+            receivers: 1,
+        }),
+        wakers: Mutex::new(vec![]),
+    });
+    (
+        Sender {
+            shared: shared.clone(),
+        },
+        Receiver { shared, next: 0 },
+    )
+}
+
+/// Sending half of broadcast channel.
+#[derive(Debug)]
+pub struct Sender<T> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T> Sender<T> {
+    /// Send value to all current receivers, returning their number.
+    ///
+    /// Fails if there are no receivers.
+    pub fn send(&self, value: T) -> Result<usize, SendError<T>> {
+        let receivers = {
+            let mut buffer = self.shared.buffer.write();
+            if buffer.receivers == 0 {
+                return Err(SendError(value));
+            }
+            let index = (buffer.tail % buffer.capacity()) as usize;
+            buffer.slots[index] = Some(value);
+            buffer.tail += 1;
+            buffer.receivers
+        };
+        self.shared.wake_all();
+        Ok(receivers)
+    }
+
+    /// Create new receiver, receiving values sent from now on.
+    pub fn subscribe(&self) -> Receiver<T> {
+        let mut buffer = self.shared.buffer.write();
+        buffer.receivers += 1;
+        Receiver {
+            shared: self.shared.clone(),
+            next: buffer.tail,
+        }
+    }
+
+    /// Returns number of active receivers.
+    pub fn receiver_count(&self) -> usize {
+        self.shared.buffer.read().receivers
+    }
+}
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Self {
+        self.shared.buffer.write().senders += 1;
+        Sender {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        let mut buffer = self.shared.buffer.write();
+        buffer.senders -= 1;
+        if buffer.senders == 0 {
+            drop(buffer);
+            self.shared.wake_all();
+        }
+    }
+}
+
+/// Receiving half of broadcast channel.
+///
+/// Implements [Stream] ending once channel is closed.
+#[derive(Debug)]
+pub struct Receiver<T> {
+    shared: Arc<Shared<T>>,
+    /// Position of next value to be received.
+    next: u64,
+}
+
+impl<T> Receiver<T> {
+    /// Create new receiver, receiving values sent from now on.
+    pub fn resubscribe(&self) -> Self {
+        let mut buffer = self.shared.buffer.write();
+        buffer.receivers += 1;
+        Receiver {
+            shared: self.shared.clone(),
+            next: buffer.tail,
+        }
+    }
+
+    /// Returns number of values waiting to be received (including skipped ones).
+    pub fn len(&self) -> usize {
+        (self.shared.buffer.read().tail - self.next) as usize
+    }
+
+    /// Returns [true] if there are no values waiting to be received.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<T> Receiver<T>
+where
+    T: Clone,
+{
+    /// Receive next value.
+    pub async fn recv(&mut self) -> Result<T, RecvError> {
+        poll_fn(|cx| self.poll_recv(cx)).await
+    }
+
+    /// Receive next value if available.
+    pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
+        let buffer = self.shared.buffer.read();
+        buffer.take(&mut self.next)
+    }
+
+    /// Poll for next value.
+    pub fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Result<T, RecvError>> {
+        let buffer = self.shared.buffer.read();
+        match buffer.take(&mut self.next) {
+            Ok(value) => Poll::Ready(Ok(value)),
+            Err(TryRecvError::Closed) => Poll::Ready(Err(RecvError::Closed)),
+            Err(TryRecvError::Lagged(skipped)) => Poll::Ready(Err(RecvError::Lagged(skipped))),
+            Err(TryRecvError::Empty) => {
+                // Registered while buffer is locked, so no value can be sent in between.
+                let mut wakers = self.shared.wakers.lock();
+                if !wakers.iter().any(|waker| waker.will_wake(cx.waker())) {
+                    wakers.push(cx.waker().clone());
+                }
+                Poll::Pending
+            }
+        }
+    }
+
+    /// Convert into stream silently skipping lagged values.
+    pub fn skip_lagged(self) -> impl Stream<Item = T> {
+        self.filter_map(|result| ready(result.ok()))
+    }
+}
+
+impl<T> Stream for Receiver<T>
+where
+    T: Clone,
+{
+    type Item = Result<T, Lagged>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.poll_recv(cx).map(|result| match result {
+            Ok(value) => Some(Ok(value)),
+            Err(RecvError::Lagged(skipped)) => Some(Err(Lagged(skipped))),
+            Err(RecvError::Closed) => None,
+        })
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        self.shared.buffer.write().receivers -= 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use futures::StreamExt;
+
+    use super::{channel, Lagged, RecvError, TryRecvError};
+    use crate::{
+        spawn,
+        test::{dtest, dtest_configure},
+        time::sleep,
+    };
+
+    dtest_configure!();
+
+    #[dtest]
+    async fn test_broadcast() {
+        let (sender, mut receiver) = channel(4);
+        let mut other_receiver = sender.subscribe();
+        assert_eq!(2, sender.receiver_count());
+        assert_eq!(Err(TryRecvError::Empty), receiver.try_recv());
+
+        let join_handle = spawn(async move {
+            let mut values = vec![];
+            while let Ok(value) = other_receiver.recv().await {
+                values.push(value);
+            }
+            values
+        });
+        sleep(Duration::from_millis(10)).await;
+        assert_eq!(Ok(2), sender.send(1));
+        assert_eq!(Ok(2), sender.send(2));
+        assert_eq!(Ok(1), receiver.recv().await);
+        assert_eq!(Ok(2), receiver.recv().await);
+
+        let mut late_receiver = receiver.resubscribe();
+        sender.send(3).unwrap();
+        drop(sender);
+        assert_eq!(vec![1, 2, 3], join_handle.await.unwrap());
+        assert_eq!(Ok(3), late_receiver.recv().await);
+        assert_eq!(Err(RecvError::Closed), late_receiver.recv().await);
+        assert_eq!(vec![Ok(3)], receiver.collect::<Vec<_>>().await);
+    }
+
+    #[dtest]
+    async fn test_broadcast_lagged() {
+        let (sender, mut receiver) = channel(2);
+        let other_receiver = sender.subscribe();
+        for i in 0..5 {
+            sender.send(i).unwrap();
+        }
+        assert_eq!(5, receiver.len());
+        assert_eq!(Err(RecvError::Lagged(3)), receiver.recv().await);
+        assert_eq!(Ok(3), receiver.recv().await);
+        assert_eq!(Ok(4), receiver.recv().await);
+
+        drop(sender);
+        assert_eq!(
+            vec![Err(Lagged(3)), Ok(3), Ok(4)],
+            other_receiver.collect::<Vec<_>>().await
+        );
+
+        let (sender, receiver) = channel(2);
+        for i in 0..3 {
+            sender.send(i).unwrap();
+        }
+        drop(sender);
+        assert_eq!(vec![1, 2], receiver.skip_lagged().collect::<Vec<_>>().await);
+
+        let (sender, receiver) = channel(2);
+        drop(receiver);
+        assert_eq!(1, sender.send(1).unwrap_err().0);
+    }
+}
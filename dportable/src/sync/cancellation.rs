@@ -0,0 +1,176 @@
+//! Hierarchical cancellation token.
+
+use std::{
+    future::Future,
+    pin::pin,
+    sync::{Arc, Weak},
+};
+
+use futures::future::{select, Either};
+
+use crate::{value::Notifier, Mutex};
+
+/// Token signalling cancellation request to tasks.
+///
+/// Cancelling a token cancels all its child tokens (recursively),
+/// but cancelling a child token doesn't affect its parent.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    inner: Arc<Node>,
+}
+
+#[derive(Debug, Default)]
+struct Node {
+    notifier: Notifier,
+    children: Mutex<Vec<Weak<Node>>>,
+}
+
+impl Node {
+    fn cancel(&self) {
+        self.notifier.notify();
+        let children = std::mem::take(&mut *self.children.lock());
+        for child in children.iter().filter_map(Weak::upgrade) {
+            child.cancel();
+        }
+    }
+}
+
+impl CancellationToken {
+    /// Create new cancellation token.
+    pub fn new() -> Self {
+        CancellationToken::default()
+    }
+
+    /// Create child token, cancelled whenever this token is cancelled.
+    pub fn child_token(&self) -> CancellationToken {
+        let child = CancellationToken::new();
+        let mut children = self.inner.children.lock();
+        if self.is_cancelled() {
+            child.cancel();
+        } else {
+            children.retain(|child| child.strong_count() > 0);
+            children.push(Arc::downgrade(&child.inner));
+        }
+        child
+    }
+
+    /// Cancel this token and all its children.
+    pub fn cancel(&self) {
+        self.inner.cancel();
+    }
+
+    /// Returns [true] if token was cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.notifier.already_notified()
+    }
+
+    /// Wait until token is cancelled.
+    pub async fn cancelled(&self) {
+        self.inner.notifier.clone().await
+    }
+
+    /// Run future until it completes or token is cancelled.
+    ///
+    /// Returns [None] if token was cancelled first, future is dropped in that case.
+    pub async fn run_until_cancelled<F>(&self, future: F) -> Option<F::Output>
+    where
+        F: Future,
+    {
+        if self.is_cancelled() {
+            return None;
+        }
+        match select(pin!(future), self.inner.notifier.clone()).await {
+            Either::Left((output, _)) => Some(output),
+            Either::Right(_) => None,
+        }
+    }
+
+    /// Return guard cancelling this token when dropped.
+    pub fn drop_guard(self) -> DropGuard {
+        DropGuard(Some(self))
+    }
+}
+
+/// Guard cancelling its token when dropped.
+#[derive(Debug)]
+pub struct DropGuard(Option<CancellationToken>);
+
+impl DropGuard {
+    /// Return token without cancelling it.
+    pub fn disarm(mut self) -> CancellationToken {
+        self.0.take().unwrap()
+    }
+}
+
+impl Drop for DropGuard {
+    fn drop(&mut self) {
+        if let Some(token) = self.0.take() {
+            token.cancel();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::test::{dtest, dtest_configure};
+    use crate::{spawn, time::sleep};
+
+    use super::CancellationToken;
+
+    dtest_configure!();
+
+    #[dtest]
+    async fn test_cancellation_token() {
+        let token = CancellationToken::new();
+        let child = token.child_token();
+        let grandchild = child.child_token();
+        let other_child = token.child_token();
+
+        child.cancel();
+        assert!(!token.is_cancelled());
+        assert!(child.is_cancelled());
+        assert!(grandchild.is_cancelled());
+        assert!(!other_child.is_cancelled());
+        grandchild.cancelled().await;
+
+        let join_handle = spawn({
+            let other_child = other_child.clone();
+            async move { other_child.cancelled().await }
+        });
+        sleep(Duration::from_millis(10)).await;
+        token.cancel();
+        join_handle.await.unwrap();
+        assert!(other_child.is_cancelled());
+        assert!(token.child_token().is_cancelled());
+    }
+
+    #[dtest]
+    async fn test_run_until_cancelled() {
+        let token = CancellationToken::new();
+        assert_eq!(Some(2), token.run_until_cancelled(async { 2 }).await);
+
+        let token_clone = token.clone();
+        spawn(async move {
+            sleep(Duration::from_millis(10)).await;
+            token_clone.cancel();
+        });
+        let result = token
+            .run_until_cancelled(sleep(Duration::from_secs(10)))
+            .await;
+        assert_eq!(None, result);
+        assert_eq!(None, token.run_until_cancelled(async { 2 }).await);
+    }
+
+    #[dtest]
+    async fn test_drop_guard() {
+        let token = CancellationToken::new();
+        drop(token.clone().drop_guard());
+        assert!(token.is_cancelled());
+
+        let token = CancellationToken::new();
+        let token = token.drop_guard().disarm();
+        assert!(!token.is_cancelled());
+    }
+}
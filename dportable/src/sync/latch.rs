@@ -0,0 +1,81 @@
+//! Async count down latch.
+
+use crate::{value::Notifier, Mutex};
+
+/// Latch releasing waiting tasks once it is counted down to zero.
+///
+/// Unlike [Barrier](super::Barrier) latch can't be reused.
+#[derive(Debug)]
+pub struct CountDownLatch {
+    count: Mutex<usize>,
+    notifier: Notifier,
+}
+
+impl CountDownLatch {
+    /// Create new latch that has to be counted down `count` times.
+    pub fn new(count: usize) -> Self {
+        let notifier = Notifier::new();
+        if count == 0 {
+            notifier.notify();
+        }
+        CountDownLatch {
+            count: Mutex::new(count),
+            notifier,
+        }
+    }
+
+    /// Decrement count, releasing waiting tasks if it reaches zero.
+    pub fn count_down(&self) {
+        let mut count = self.count.lock();
+        if *count > 0 {
+            *count -= 1;
+            if *count == 0 {
+                self.notifier.notify();
+            }
+        }
+    }
+
+    /// Return current count.
+    pub fn count(&self) -> usize {
+        *self.count.lock()
+    }
+
+    /// Wait until count reaches zero.
+    pub async fn wait(&self) {
+        self.notifier.clone().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, time::Duration};
+
+    use futures::FutureExt;
+
+    use crate::test::{dtest, dtest_configure};
+    use crate::{spawn, time::sleep};
+
+    use super::CountDownLatch;
+
+    dtest_configure!();
+
+    #[dtest]
+    async fn test_count_down_latch() {
+        let latch = Arc::new(CountDownLatch::new(3));
+        for _ in 0..3 {
+            let latch = latch.clone();
+            spawn(async move {
+                sleep(Duration::from_millis(10)).await;
+                latch.count_down();
+            });
+        }
+        assert!(latch.wait().now_or_never().is_none());
+        latch.wait().await;
+        assert_eq!(0, latch.count());
+        latch.count_down();
+        assert_eq!(0, latch.count());
+        latch.wait().await;
+
+        CountDownLatch::new(0).wait().await;
+    }
+}
@@ -0,0 +1,152 @@
+//! Asynchronous synchronization primitives.
+
+#[cfg(not(target_arch = "wasm32"))]
+mod native;
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use native::*;
+
+#[cfg(target_arch = "wasm32")]
+mod wasm;
+
+#[cfg(target_arch = "wasm32")]
+pub use wasm::*;
+
+mod barrier;
+pub use barrier::*;
+
+pub mod broadcast;
+
+mod cancellation;
+pub use cancellation::*;
+
+mod latch;
+pub use latch::*;
+
+pub mod mpsc;
+
+mod wait_group;
+pub use wait_group::*;
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, time::Duration};
+
+    use futures::FutureExt;
+
+    use super::{Notify, Semaphore, TryAcquireError};
+    use crate::{
+        spawn,
+        test::{dtest, dtest_configure},
+        time::sleep,
+        Mutex,
+    };
+
+    dtest_configure!();
+
+    #[dtest]
+    async fn test_semaphore() {
+        let semaphore = Semaphore::new(2);
+        let permit = semaphore.acquire().await.unwrap();
+        assert_eq!(1, semaphore.available_permits());
+        let _other_permit = semaphore.try_acquire().unwrap();
+        assert_eq!(
+            TryAcquireError::NoPermits,
+            semaphore.try_acquire().unwrap_err()
+        );
+        drop(permit);
+        assert_eq!(1, semaphore.available_permits());
+
+        semaphore.add_permits(2);
+        let permit = semaphore.acquire_many(3).await.unwrap();
+        assert_eq!(3, permit.num_permits());
+        assert_eq!(0, semaphore.available_permits());
+        permit.forget();
+        assert_eq!(0, semaphore.available_permits());
+
+        semaphore.close();
+        assert!(semaphore.is_closed());
+        assert!(semaphore.acquire().await.is_err());
+        assert_eq!(
+            TryAcquireError::Closed,
+            semaphore.try_acquire().unwrap_err()
+        );
+    }
+
+    #[dtest]
+    async fn test_semaphore_owned_permits() {
+        let semaphore = Arc::new(Semaphore::new(2));
+        let running = Arc::new(Mutex::new((0, 0)));
+        let mut join_handles = vec![];
+        for _ in 0..5 {
+            let semaphore = semaphore.clone();
+            let running = running.clone();
+            join_handles.push(spawn(async move {
+                let _permit = semaphore.acquire_owned().await.unwrap();
+                {
+                    let mut running = running.lock();
+                    running.0 += 1;
+                    running.1 = running.1.max(running.0);
+                }
+                sleep(Duration::from_millis(10)).await;
+                running.lock().0 -= 1;
+            }));
+        }
+        for join_handle in join_handles {
+            join_handle.await.unwrap();
+        }
+        assert_eq!((0, 2), *running.lock());
+        assert_eq!(2, semaphore.available_permits());
+    }
+
+    #[dtest]
+    async fn test_semaphore_close_wakes_waiters() {
+        let semaphore = Arc::new(Semaphore::new(0));
+        let semaphore_clone = semaphore.clone();
+        let join_handle = spawn(async move { semaphore_clone.acquire_owned().await.is_err() });
+        sleep(Duration::from_millis(10)).await;
+        semaphore.close();
+        assert!(join_handle.await.unwrap());
+    }
+
+    #[dtest]
+    async fn test_notify_one() {
+        let notify = Notify::new();
+        notify.notify_one();
+        notify.notify_one();
+        notify.notified().await;
+        assert!(notify.notified().now_or_never().is_none());
+
+        let notify = Arc::new(Notify::new());
+        let notify_clone = notify.clone();
+        let join_handle = spawn(async move {
+            for _ in 0..3 {
+                notify_clone.notified().await;
+            }
+        });
+        for _ in 0..3 {
+            sleep(Duration::from_millis(10)).await;
+            notify.notify_one();
+        }
+        join_handle.await.unwrap();
+    }
+
+    #[dtest]
+    async fn test_notify_waiters() {
+        let notify = Arc::new(Notify::new());
+        let join_handles: Vec<_> = (0..2)
+            .map(|_| {
+                let notify = notify.clone();
+                spawn(async move { notify.notified().await })
+            })
+            .collect();
+        let notified = notify.notified();
+        sleep(Duration::from_millis(10)).await;
+        notify.notify_waiters();
+        notified.await;
+        for join_handle in join_handles {
+            join_handle.await.unwrap();
+        }
+        assert!(notify.notified().now_or_never().is_none());
+    }
+}
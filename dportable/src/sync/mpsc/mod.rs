@@ -0,0 +1,114 @@
+//! Multi-producer, single-consumer channels.
+//!
+//! [channel] has bounded capacity, its senders wait for free space in `send().await`.
+//! [unbounded_channel] never blocks senders.
+//!
+//! Using [tokio](https://docs.rs/tokio/latest/tokio/sync/mpsc/index.html) on native platforms,
+//! receivers also implement [Stream](futures::Stream) on all targets.
+
+#[cfg(not(target_arch = "wasm32"))]
+mod native;
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use native::*;
+
+#[cfg(target_arch = "wasm32")]
+mod wasm;
+
+#[cfg(target_arch = "wasm32")]
+pub use wasm::*;
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use futures::StreamExt;
+
+    use super::{channel, unbounded_channel, TryRecvError, TrySendError};
+    use crate::{
+        spawn,
+        test::{dtest, dtest_configure},
+        time::sleep,
+    };
+
+    dtest_configure!();
+
+    #[dtest]
+    async fn test_channel() {
+        let (sender, mut receiver) = channel(2);
+        assert_eq!(2, sender.max_capacity());
+        sender.send(1).await.unwrap();
+        sender.try_send(2).unwrap();
+        assert_eq!(0, sender.capacity());
+        assert!(matches!(sender.try_send(3), Err(TrySendError::Full(3))));
+
+        let sender_clone = sender.clone();
+        assert_eq!(2, receiver.sender_strong_count());
+        let join_handle = spawn(async move {
+            sender_clone.send(3).await.unwrap();
+            sender_clone.send(4).await.unwrap();
+        });
+        sleep(Duration::from_millis(10)).await;
+        assert!(!join_handle.is_finished());
+        assert_eq!(2, receiver.len());
+
+        assert_eq!(Some(1), receiver.recv().await);
+        assert_eq!(Some(2), receiver.recv().await);
+        join_handle.await.unwrap();
+        assert_eq!(1, receiver.sender_strong_count());
+        drop(sender);
+        assert_eq!(vec![3, 4], receiver.collect::<Vec<_>>().await);
+    }
+
+    #[dtest]
+    async fn test_channel_close() {
+        let (sender, mut receiver) = channel(1);
+        sender.send(1).await.unwrap();
+        let sender_clone = sender.clone();
+        let join_handle = spawn(async move { sender_clone.send(2).await });
+        let closed = spawn({
+            let sender = sender.clone();
+            async move { sender.closed().await }
+        });
+        sleep(Duration::from_millis(10)).await;
+        assert!(!sender.is_closed());
+
+        receiver.close();
+        assert!(sender.is_closed());
+        assert!(receiver.is_closed());
+        assert_eq!(2, join_handle.await.unwrap().unwrap_err().0);
+        closed.await.unwrap();
+        assert!(matches!(sender.try_send(3), Err(TrySendError::Closed(3))));
+        assert_eq!(Some(1), receiver.recv().await);
+        assert_eq!(None, receiver.recv().await);
+        assert_eq!(Err(TryRecvError::Disconnected), receiver.try_recv());
+    }
+
+    #[dtest]
+    async fn test_unbounded_channel() {
+        let (sender, mut receiver) = unbounded_channel();
+        assert_eq!(Err(TryRecvError::Empty), receiver.try_recv());
+        for i in 0..100 {
+            sender.send(i).unwrap();
+        }
+        assert_eq!(100, receiver.len());
+        assert_eq!(Ok(0), receiver.try_recv());
+
+        let sender_clone = sender.clone();
+        assert_eq!(2, sender.strong_count());
+        spawn(async move {
+            sleep(Duration::from_millis(10)).await;
+            sender_clone.send(100).unwrap();
+        });
+        drop(sender);
+        assert_eq!(
+            (1..=100).collect::<Vec<_>>(),
+            receiver.collect::<Vec<_>>().await
+        );
+
+        let (sender, receiver) = unbounded_channel();
+        drop(receiver);
+        assert!(sender.is_closed());
+        assert_eq!(1, sender.send(1).unwrap_err().0);
+    }
+}
@@ -0,0 +1,479 @@
+use std::{
+    cell::RefCell,
+    collections::VecDeque,
+    fmt::{Debug, Display},
+    future::poll_fn,
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll, Waker},
+};
+
+use futures::Stream;
+
+use crate::sync::{Notify, Semaphore, TryAcquireError};
+
+#[derive(PartialEq, Eq, Clone, Copy)]
+pub struct SendError<T>(pub T);
+
+impl<T> Debug for SendError<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SendError").finish_non_exhaustive()
+    }
+}
+
+impl<T> Display for SendError<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "channel closed")
+    }
+}
+
+impl<T> std::error::Error for SendError<T> {}
+
+#[derive(PartialEq, Eq, Clone, Copy)]
+pub enum TrySendError<T> {
+    Full(T),
+    Closed(T),
+}
+
+impl<T> TrySendError<T> {
+    pub fn into_inner(self) -> T {
+        match self {
+            TrySendError::Full(value) | TrySendError::Closed(value) => value,
+        }
+    }
+}
+
+impl<T> Debug for TrySendError<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TrySendError::Full(_) => write!(f, "Full(..)"),
+            TrySendError::Closed(_) => write!(f, "Closed(..)"),
+        }
+    }
+}
+
+impl<T> Display for TrySendError<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TrySendError::Full(_) => write!(f, "no available capacity"),
+            TrySendError::Closed(_) => write!(f, "channel closed"),
+        }
+    }
+}
+
+impl<T> std::error::Error for TrySendError<T> {}
+
+impl<T> From<SendError<T>> for TrySendError<T> {
+    fn from(error: SendError<T>) -> Self {
+        TrySendError::Closed(error.0)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum TryRecvError {
+    Empty,
+    Disconnected,
+}
+
+impl Display for TryRecvError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TryRecvError::Empty => write!(f, "receiving on an empty channel"),
+            TryRecvError::Disconnected => write!(f, "receiving on a closed channel"),
+        }
+    }
+}
+
+impl std::error::Error for TryRecvError {}
+
+/// State shared by both ends of a channel.
+#[derive(Debug)]
+struct Chan<T> {
+    state: RefCell<State<T>>,
+    /// Free buffer slots of bounded channel.
+    semaphore: Option<(Semaphore, usize)>,
+    closed: Notify,
+}
+
+#[derive(Debug)]
+struct State<T> {
+    queue: VecDeque<T>,
+    senders: usize,
+    receiver_closed: bool,
+    receiver_waker: Option<Waker>,
+}
+
+impl<T> Chan<T> {
+    fn new(bound: Option<usize>) -> Rc<Self> {
+        Rc::new(Chan {
+            state: RefCell::new(State {
+                queue: VecDeque::new(),
+                senders: 1,
+                receiver_closed: false,
+                receiver_waker: None,
+            }),
+            semaphore: bound.map(|bound| (Semaphore::new(bound), bound)),
+            closed: Notify::new(),
+        })
+    }
+
+    fn push(&self, value: T) -> Result<(), SendError<T>> {
+        let waker = {
+            let mut state = self.state.borrow_mut();
+            if state.receiver_closed {
+                return Err(SendError(value));
+            }
+            state.queue.push_back(value);
+            state.receiver_waker.take()
+        };
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+        Ok(())
+    }
+
+    async fn send(&self, value: T) -> Result<(), SendError<T>> {
+        if let Some((semaphore, _)) = &self.semaphore {
+            match semaphore.acquire().await {
+                Ok(permit) => permit.forget(),
+                Err(_) => return Err(SendError(value)),
+            }
+        }
+        self.push(value)
+    }
+
+    fn try_send(&self, value: T) -> Result<(), TrySendError<T>> {
+        if let Some((semaphore, _)) = &self.semaphore {
+            match semaphore.try_acquire() {
+                Ok(permit) => permit.forget(),
+                Err(TryAcquireError::NoPermits) => return Err(TrySendError::Full(value)),
+                Err(TryAcquireError::Closed) => return Err(TrySendError::Closed(value)),
+            }
+        }
+        Ok(self.push(value)?)
+    }
+
+    async fn closed(&self) {
+        loop {
+            let notified = self.closed.notified();
+            if self.is_closed() {
+                return;
+            }
+            notified.await;
+        }
+    }
+
+    fn is_closed(&self) -> bool {
+        self.state.borrow().receiver_closed
+    }
+
+    fn try_recv(&self) -> Result<T, TryRecvError> {
+        let mut state = self.state.borrow_mut();
+        match state.queue.pop_front() {
+            Some(value) => {
+                drop(state);
+                if let Some((semaphore, _)) = &self.semaphore {
+                    semaphore.add_permits(1);
+                }
+                Ok(value)
+            }
+            None if state.senders == 0 || state.receiver_closed => Err(TryRecvError::Disconnected),
+            None => Err(TryRecvError::Empty),
+        }
+    }
+
+    fn poll_recv(&self, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        match self.try_recv() {
+            Ok(value) => Poll::Ready(Some(value)),
+            Err(TryRecvError::Disconnected) => Poll::Ready(None),
+            Err(TryRecvError::Empty) => {
+                self.state.borrow_mut().receiver_waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+
+    fn close(&self) {
+        self.state.borrow_mut().receiver_closed = true;
+        if let Some((semaphore, _)) = &self.semaphore {
+            semaphore.close();
+        }
+        self.closed.notify_waiters();
+    }
+
+    fn is_receiver_closed(&self) -> bool {
+        let state = self.state.borrow();
+        state.receiver_closed || state.senders == 0
+    }
+
+    fn len(&self) -> usize {
+        self.state.borrow().queue.len()
+    }
+
+    fn capacity(&self) -> usize {
+        self.semaphore
+            .as_ref()
+            .map(|(semaphore, _)| semaphore.available_permits())
+            .unwrap_or(usize::MAX)
+    }
+
+    fn max_capacity(&self) -> usize {
+        self.semaphore
+            .as_ref()
+            .map(|(_, bound)| *bound)
+            .unwrap_or(usize::MAX)
+    }
+
+    fn sender_count(&self) -> usize {
+        self.state.borrow().senders
+    }
+
+    fn add_sender(&self) {
+        self.state.borrow_mut().senders += 1;
+    }
+
+    fn remove_sender(&self) {
+        let waker = {
+            let mut state = self.state.borrow_mut();
+            state.senders -= 1;
+            if state.senders > 0 {
+                return;
+            }
+            state.receiver_waker.take()
+        };
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+
+    fn drop_receiver(&self) {
+        self.close();
+        let queue = std::mem::take(&mut self.state.borrow_mut().queue);
+        drop(queue);
+    }
+}
+
+#[track_caller]
+pub fn channel<T>(buffer: usize) -> (Sender<T>, Receiver<T>) {
+    assert!(buffer > 0, "mpsc bounded channel requires buffer > 0");
+    let chan = Chan::new(Some(buffer));
+    (Sender { chan: chan.clone() }, Receiver { chan })
+}
+
+pub fn unbounded_channel<T>() -> (UnboundedSender<T>, UnboundedReceiver<T>) {
+    let chan = Chan::new(None);
+    (
+        UnboundedSender { chan: chan.clone() },
+        UnboundedReceiver { chan },
+    )
+}
+
+#[derive(Debug)]
+pub struct Sender<T> {
+    chan: Rc<Chan<T>>,
+}
+
+impl<T> Sender<T> {
+    pub async fn send(&self, value: T) -> Result<(), SendError<T>> {
+        self.chan.send(value).await
+    }
+
+    pub fn try_send(&self, value: T) -> Result<(), TrySendError<T>> {
+        self.chan.try_send(value)
+    }
+
+    pub async fn closed(&self) {
+        self.chan.closed().await
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.chan.is_closed()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.chan.capacity()
+    }
+
+    pub fn max_capacity(&self) -> usize {
+        self.chan.max_capacity()
+    }
+
+    pub fn strong_count(&self) -> usize {
+        self.chan.sender_count()
+    }
+
+    pub fn same_channel(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.chan, &other.chan)
+    }
+}
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Self {
+        self.chan.add_sender();
+        Sender {
+            chan: self.chan.clone(),
+        }
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        self.chan.remove_sender();
+    }
+}
+
+#[derive(Debug)]
+pub struct UnboundedSender<T> {
+    chan: Rc<Chan<T>>,
+}
+
+impl<T> UnboundedSender<T> {
+    pub fn send(&self, value: T) -> Result<(), SendError<T>> {
+        self.chan.push(value)
+    }
+
+    pub async fn closed(&self) {
+        self.chan.closed().await
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.chan.is_closed()
+    }
+
+    pub fn strong_count(&self) -> usize {
+        self.chan.sender_count()
+    }
+
+    pub fn same_channel(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.chan, &other.chan)
+    }
+}
+
+impl<T> Clone for UnboundedSender<T> {
+    fn clone(&self) -> Self {
+        self.chan.add_sender();
+        UnboundedSender {
+            chan: self.chan.clone(),
+        }
+    }
+}
+
+impl<T> Drop for UnboundedSender<T> {
+    fn drop(&mut self) {
+        self.chan.remove_sender();
+    }
+}
+
+#[derive(Debug)]
+pub struct Receiver<T> {
+    chan: Rc<Chan<T>>,
+}
+
+impl<T> Receiver<T> {
+    pub async fn recv(&mut self) -> Option<T> {
+        poll_fn(|cx| self.chan.poll_recv(cx)).await
+    }
+
+    pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
+        self.chan.try_recv()
+    }
+
+    pub fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        self.chan.poll_recv(cx)
+    }
+
+    pub fn close(&mut self) {
+        self.chan.close()
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.chan.is_receiver_closed()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.chan.len() == 0
+    }
+
+    pub fn len(&self) -> usize {
+        self.chan.len()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.chan.capacity()
+    }
+
+    pub fn max_capacity(&self) -> usize {
+        self.chan.max_capacity()
+    }
+
+    pub fn sender_strong_count(&self) -> usize {
+        self.chan.sender_count()
+    }
+}
+
+impl<T> Stream for Receiver<T> {
+    type Item = T;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.chan.poll_recv(cx)
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        self.chan.drop_receiver();
+    }
+}
+
+#[derive(Debug)]
+pub struct UnboundedReceiver<T> {
+    chan: Rc<Chan<T>>,
+}
+
+impl<T> UnboundedReceiver<T> {
+    pub async fn recv(&mut self) -> Option<T> {
+        poll_fn(|cx| self.chan.poll_recv(cx)).await
+    }
+
+    pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
+        self.chan.try_recv()
+    }
+
+    pub fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        self.chan.poll_recv(cx)
+    }
+
+    pub fn close(&mut self) {
+        self.chan.close()
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.chan.is_receiver_closed()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.chan.len() == 0
+    }
+
+    pub fn len(&self) -> usize {
+        self.chan.len()
+    }
+
+    pub fn sender_strong_count(&self) -> usize {
+        self.chan.sender_count()
+    }
+}
+
+impl<T> Stream for UnboundedReceiver<T> {
+    type Item = T;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.chan.poll_recv(cx)
+    }
+}
+
+impl<T> Drop for UnboundedReceiver<T> {
+    fn drop(&mut self) {
+        self.chan.drop_receiver();
+    }
+}
@@ -0,0 +1,4 @@
+pub use tokio::sync::{
+    futures::Notified, AcquireError, Notify, OwnedSemaphorePermit, Semaphore, SemaphorePermit,
+    TryAcquireError,
+};
@@ -0,0 +1,111 @@
+//! Wait group.
+
+use std::sync::Arc;
+
+use crate::{value::Notifier, Mutex};
+
+/// Wait group tracking completion of dynamically spawned tasks.
+///
+/// Every task gets its own clone of the wait group and drops it once done,
+/// [WaitGroup::wait] resolves when all clones are gone.
+///
+/// ```
+/// use dportable::{spawn, sync::WaitGroup};
+///
+/// async fn spawn_and_wait() {
+///     let wait_group = WaitGroup::new();
+///     for _ in 0..3 {
+///         let wait_group = wait_group.clone();
+///         spawn(async move {
+///             // do some work...
+///             drop(wait_group);
+///         });
+///     }
+///     wait_group.wait().await;
+/// }
+/// ```
+#[derive(Debug)]
+pub struct WaitGroup {
+    inner: Arc<Inner>,
+}
+
+#[derive(Debug)]
+struct Inner {
+    count: Mutex<usize>,
+    notifier: Notifier,
+}
+
+impl WaitGroup {
+    /// Create new wait group.
+    pub fn new() -> Self {
+        WaitGroup {
+            inner: Arc::new(Inner {
+                count: Mutex::new(1),
+                notifier: Notifier::new(),
+            }),
+        }
+    }
+
+    /// Drop this wait group and wait until all its clones are dropped.
+    pub async fn wait(self) {
+        let notifier = self.inner.notifier.clone();
+        drop(self);
+        notifier.await
+    }
+}
+
+impl Default for WaitGroup {
+    fn default() -> Self {
+        WaitGroup::new()
+    }
+}
+
+impl Clone for WaitGroup {
+    fn clone(&self) -> Self {
+        *self.inner.count.lock() += 1;
+        WaitGroup {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl Drop for WaitGroup {
+    fn drop(&mut self) {
+        let mut count = self.inner.count.lock();
+        *count -= 1;
+        if *count == 0 {
+            self.inner.notifier.notify();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, time::Duration};
+
+    use crate::test::{dtest, dtest_configure};
+    use crate::{spawn, time::sleep, Mutex};
+
+    use super::WaitGroup;
+
+    dtest_configure!();
+
+    #[dtest]
+    async fn test_wait_group() {
+        let wait_group = WaitGroup::new();
+        let finished = Arc::new(Mutex::new(0));
+        for i in 0..5 {
+            let wait_group = wait_group.clone();
+            let finished = finished.clone();
+            spawn(async move {
+                sleep(Duration::from_millis(5 * i)).await;
+                *finished.lock() += 1;
+                drop(wait_group);
+            });
+        }
+        wait_group.wait().await;
+        assert_eq!(5, *finished.lock());
+
+        WaitGroup::new().wait().await;
+    }
+}
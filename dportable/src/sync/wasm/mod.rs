@@ -0,0 +1,5 @@
+mod notify;
+pub use notify::*;
+
+mod semaphore;
+pub use semaphore::*;
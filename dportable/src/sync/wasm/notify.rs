@@ -0,0 +1,147 @@
+use std::{
+    cell::RefCell,
+    collections::VecDeque,
+    future::Future,
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll, Waker},
+};
+
+#[derive(Debug, Default)]
+pub struct Notify {
+    state: RefCell<State>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    permit: bool,
+    notify_waiters_calls: usize,
+    waiters: VecDeque<Rc<RefCell<Waiter>>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Notification {
+    One,
+    All,
+}
+
+#[derive(Debug, Default)]
+struct Waiter {
+    notification: Option<Notification>,
+    waker: Option<Waker>,
+}
+
+impl Notify {
+    pub fn new() -> Self {
+        Notify::default()
+    }
+
+    pub fn notified(&self) -> Notified<'_> {
+        Notified {
+            notify: self,
+            notify_waiters_calls: self.state.borrow().notify_waiters_calls,
+            waiter: None,
+        }
+    }
+
+    pub fn notify_one(&self) {
+        let waiter = {
+            let mut state = self.state.borrow_mut();
+            match state.waiters.pop_front() {
+                Some(waiter) => waiter,
+                None => {
+                    state.permit = true;
+                    return;
+                }
+            }
+        };
+        let waker = {
+            let mut waiter = waiter.borrow_mut();
+            waiter.notification = Some(Notification::One);
+            waiter.waker.take()
+        };
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+
+    pub fn notify_waiters(&self) {
+        let waiters = {
+            let mut state = self.state.borrow_mut();
+            state.notify_waiters_calls += 1;
+            std::mem::take(&mut state.waiters)
+        };
+        for waiter in waiters {
+            let waker = {
+                let mut waiter = waiter.borrow_mut();
+                waiter.notification = Some(Notification::All);
+                waiter.waker.take()
+            };
+            if let Some(waker) = waker {
+                waker.wake();
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Notified<'a> {
+    notify: &'a Notify,
+    notify_waiters_calls: usize,
+    waiter: Option<Rc<RefCell<Waiter>>>,
+}
+
+impl Future for Notified<'_> {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if let Some(waiter) = &self.waiter {
+            let mut waiter_ref = waiter.borrow_mut();
+            if waiter_ref.notification.is_some() {
+                drop(waiter_ref);
+                self.waiter = None;
+                Poll::Ready(())
+            } else {
+                waiter_ref.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        } else {
+            let mut state = self.notify.state.borrow_mut();
+            if state.notify_waiters_calls != self.notify_waiters_calls {
+                Poll::Ready(())
+            } else if state.permit {
+                state.permit = false;
+                Poll::Ready(())
+            } else {
+                let waiter = Rc::new(RefCell::new(Waiter {
+                    notification: None,
+                    waker: Some(cx.waker().clone()),
+                }));
+                state.waiters.push_back(waiter.clone());
+                drop(state);
+                self.waiter = Some(waiter);
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl Drop for Notified<'_> {
+    fn drop(&mut self) {
+        if let Some(waiter) = self.waiter.take() {
+            let notification = waiter.borrow().notification;
+            match notification {
+                // Pass unconsumed notification on to another waiter.
+                Some(Notification::One) => self.notify.notify_one(),
+                Some(Notification::All) => (),
+                None => self
+                    .notify
+                    .state
+                    .borrow_mut()
+                    .waiters
+                    .retain(|other| !Rc::ptr_eq(other, &waiter)),
+            }
+        }
+    }
+}
@@ -0,0 +1,336 @@
+use std::{
+    cell::RefCell,
+    collections::VecDeque,
+    fmt::Display,
+    future::Future,
+    pin::Pin,
+    rc::Rc,
+    sync::Arc,
+    task::{Context, Poll, Waker},
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AcquireError(());
+
+impl Display for AcquireError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "semaphore closed")
+    }
+}
+
+impl std::error::Error for AcquireError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TryAcquireError {
+    Closed,
+    NoPermits,
+}
+
+impl TryAcquireError {
+    pub fn is_closed(&self) -> bool {
+        matches!(self, TryAcquireError::Closed)
+    }
+
+    pub fn is_no_permits(&self) -> bool {
+        matches!(self, TryAcquireError::NoPermits)
+    }
+}
+
+impl Display for TryAcquireError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TryAcquireError::Closed => write!(f, "semaphore closed"),
+            TryAcquireError::NoPermits => write!(f, "no permits available"),
+        }
+    }
+}
+
+impl std::error::Error for TryAcquireError {}
+
+/// Single-threaded FIFO semaphore, also backing async locks in WASM.
+#[derive(Debug)]
+pub struct Semaphore {
+    state: RefCell<State>,
+}
+
+#[derive(Debug)]
+struct State {
+    permits: usize,
+    closed: bool,
+    waiters: VecDeque<Rc<RefCell<Waiter>>>,
+}
+
+#[derive(Debug)]
+struct Waiter {
+    permits: usize,
+    acquired: bool,
+    waker: Option<Waker>,
+}
+
+impl Semaphore {
+    pub const MAX_PERMITS: usize = usize::MAX >> 3;
+
+    pub fn new(permits: usize) -> Self {
+        assert!(
+            permits <= Self::MAX_PERMITS,
+            "a semaphore may not have more than MAX_PERMITS permits ({})",
+            Self::MAX_PERMITS
+        );
+        Semaphore {
+            state: RefCell::new(State {
+                permits,
+                closed: false,
+                waiters: VecDeque::new(),
+            }),
+        }
+    }
+
+    pub fn available_permits(&self) -> usize {
+        self.state.borrow().permits
+    }
+
+    pub fn add_permits(&self, n: usize) {
+        let permits = self.state.borrow().permits;
+        assert!(
+            permits.saturating_add(n) <= Self::MAX_PERMITS,
+            "number of added permits ({n}) would overflow MAX_PERMITS ({})",
+            Self::MAX_PERMITS
+        );
+        self.release(n);
+    }
+
+    pub async fn acquire(&self) -> Result<SemaphorePermit<'_>, AcquireError> {
+        self.acquire_many(1).await
+    }
+
+    pub async fn acquire_many(&self, n: u32) -> Result<SemaphorePermit<'_>, AcquireError> {
+        self.acquire_permits(n as usize).await?;
+        Ok(SemaphorePermit {
+            semaphore: self,
+            permits: n,
+        })
+    }
+
+    pub fn try_acquire(&self) -> Result<SemaphorePermit<'_>, TryAcquireError> {
+        self.try_acquire_many(1)
+    }
+
+    pub fn try_acquire_many(&self, n: u32) -> Result<SemaphorePermit<'_>, TryAcquireError> {
+        self.try_acquire_permits(n as usize)?;
+        Ok(SemaphorePermit {
+            semaphore: self,
+            permits: n,
+        })
+    }
+
+    pub async fn acquire_owned(self: Arc<Self>) -> Result<OwnedSemaphorePermit, AcquireError> {
+        self.acquire_many_owned(1).await
+    }
+
+    pub async fn acquire_many_owned(
+        self: Arc<Self>,
+        n: u32,
+    ) -> Result<OwnedSemaphorePermit, AcquireError> {
+        self.acquire_permits(n as usize).await?;
+        Ok(OwnedSemaphorePermit {
+            semaphore: self,
+            permits: n,
+        })
+    }
+
+    pub fn try_acquire_owned(self: Arc<Self>) -> Result<OwnedSemaphorePermit, TryAcquireError> {
+        self.try_acquire_many_owned(1)
+    }
+
+    pub fn try_acquire_many_owned(
+        self: Arc<Self>,
+        n: u32,
+    ) -> Result<OwnedSemaphorePermit, TryAcquireError> {
+        self.try_acquire_permits(n as usize)?;
+        Ok(OwnedSemaphorePermit {
+            semaphore: self,
+            permits: n,
+        })
+    }
+
+    pub fn close(&self) {
+        let waiters = {
+            let mut state = self.state.borrow_mut();
+            state.closed = true;
+            std::mem::take(&mut state.waiters)
+        };
+        for waiter in waiters {
+            if let Some(waker) = waiter.borrow_mut().waker.take() {
+                waker.wake();
+            }
+        }
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.state.borrow().closed
+    }
+
+    pub(crate) fn acquire_permits(&self, permits: usize) -> Acquire<'_> {
+        Acquire {
+            semaphore: self,
+            permits,
+            waiter: None,
+        }
+    }
+
+    pub(crate) fn try_acquire_permits(&self, permits: usize) -> Result<(), TryAcquireError> {
+        let mut state = self.state.borrow_mut();
+        if state.closed {
+            Err(TryAcquireError::Closed)
+        } else if state.waiters.is_empty() && state.permits >= permits {
+            state.permits -= permits;
+            Ok(())
+        } else {
+            Err(TryAcquireError::NoPermits)
+        }
+    }
+
+    pub(crate) fn release(&self, permits: usize) {
+        self.state.borrow_mut().permits += permits;
+        self.assign();
+    }
+
+    /// Hand out available permits to waiters in FIFO order.
+    fn assign(&self) {
+        let mut wakers = vec![];
+        {
+            let mut state = self.state.borrow_mut();
+            while let Some(waiter) = state.waiters.front().cloned() {
+                let mut waiter = waiter.borrow_mut();
+                if waiter.permits > state.permits {
+                    break;
+                }
+                state.permits -= waiter.permits;
+                waiter.acquired = true;
+                wakers.extend(waiter.waker.take());
+                drop(waiter);
+                state.waiters.pop_front();
+            }
+        }
+        for waker in wakers {
+            waker.wake();
+        }
+    }
+}
+
+/// Future returned by [Semaphore::acquire_permits].
+#[derive(Debug)]
+pub(crate) struct Acquire<'a> {
+    semaphore: &'a Semaphore,
+    permits: usize,
+    waiter: Option<Rc<RefCell<Waiter>>>,
+}
+
+impl Future for Acquire<'_> {
+    type Output = Result<(), AcquireError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if let Some(waiter) = &self.waiter {
+            let mut waiter_ref = waiter.borrow_mut();
+            if waiter_ref.acquired {
+                drop(waiter_ref);
+                self.waiter = None;
+                Poll::Ready(Ok(()))
+            } else if self.semaphore.is_closed() {
+                drop(waiter_ref);
+                self.waiter = None;
+                Poll::Ready(Err(AcquireError(())))
+            } else {
+                waiter_ref.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        } else {
+            match self.semaphore.try_acquire_permits(self.permits) {
+                Ok(()) => Poll::Ready(Ok(())),
+                Err(TryAcquireError::Closed) => Poll::Ready(Err(AcquireError(()))),
+                Err(TryAcquireError::NoPermits) => {
+                    let waiter = Rc::new(RefCell::new(Waiter {
+                        permits: self.permits,
+                        acquired: false,
+                        waker: Some(cx.waker().clone()),
+                    }));
+                    self.semaphore
+                        .state
+                        .borrow_mut()
+                        .waiters
+                        .push_back(waiter.clone());
+                    self.waiter = Some(waiter);
+                    Poll::Pending
+                }
+            }
+        }
+    }
+}
+
+impl Drop for Acquire<'_> {
+    fn drop(&mut self) {
+        if let Some(waiter) = self.waiter.take() {
+            if waiter.borrow().acquired {
+                self.semaphore.release(self.permits);
+            } else {
+                self.semaphore
+                    .state
+                    .borrow_mut()
+                    .waiters
+                    .retain(|other| !Rc::ptr_eq(other, &waiter));
+                // Waiters queued behind this one may now be satisfiable.
+                self.semaphore.assign();
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+#[must_use]
+pub struct SemaphorePermit<'a> {
+    semaphore: &'a Semaphore,
+    permits: u32,
+}
+
+impl SemaphorePermit<'_> {
+    pub fn forget(mut self) {
+        self.permits = 0;
+    }
+
+    pub fn num_permits(&self) -> usize {
+        self.permits as usize
+    }
+}
+
+impl Drop for SemaphorePermit<'_> {
+    fn drop(&mut self) {
+        self.semaphore.release(self.permits as usize);
+    }
+}
+
+#[derive(Debug)]
+#[must_use]
+pub struct OwnedSemaphorePermit {
+    semaphore: Arc<Semaphore>,
+    permits: u32,
+}
+
+impl OwnedSemaphorePermit {
+    pub fn forget(mut self) {
+        self.permits = 0;
+    }
+
+    pub fn num_permits(&self) -> usize {
+        self.permits as usize
+    }
+
+    pub fn semaphore(&self) -> &Arc<Semaphore> {
+        &self.semaphore
+    }
+}
+
+impl Drop for OwnedSemaphorePermit {
+    fn drop(&mut self) {
+        self.semaphore.release(self.permits as usize);
+    }
+}
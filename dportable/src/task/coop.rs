@@ -0,0 +1,64 @@
+//! Cooperative scheduling.
+
+/// Yield execution back to the runtime, letting other tasks run.
+#[cfg(not(target_arch = "wasm32"))]
+pub async fn yield_now() {
+    tokio::task::yield_now().await
+}
+
+/// Yield execution back to the runtime, letting other tasks run.
+///
+/// Yields only to the microtask queue, so timers, IO and rendering still can't run
+/// until control is returned to the browser's event loop.
+#[cfg(target_arch = "wasm32")]
+pub async fn yield_now() {
+    let promise = js_sys::Promise::resolve(&js_sys::wasm_bindgen::JsValue::UNDEFINED);
+    let _ = wasm_bindgen_futures::JsFuture::from(promise).await;
+}
+
+/// Helper yielding every `n` iterations of a long-running loop.
+///
+/// In WASM it yields to the browser's event loop (through `setTimeout`),
+/// so timers, IO and rendering can run as well.
+///
+/// ```
+/// # async fn example() {
+/// use dportable::task::Budget;
+///
+/// let mut budget = Budget::new(100);
+/// for _ in 0..10_000 {
+///     // ...
+///     budget.tick().await;
+/// }
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Budget {
+    n: usize,
+    remaining: usize,
+}
+
+impl Budget {
+    /// Create new budget yielding every `n` ticks (0 is treated as 1).
+    pub fn new(n: usize) -> Self {
+        let n = n.max(1);
+        Budget { n, remaining: n }
+    }
+
+    /// Returns number of ticks left before next yield.
+    pub fn remaining(&self) -> usize {
+        self.remaining
+    }
+
+    /// Consume one unit of budget, yielding if it ran out.
+    pub async fn tick(&mut self) {
+        self.remaining -= 1;
+        if self.remaining == 0 {
+            self.remaining = self.n;
+            #[cfg(not(target_arch = "wasm32"))]
+            yield_now().await;
+            #[cfg(target_arch = "wasm32")]
+            crate::time::sleep(std::time::Duration::ZERO).await;
+        }
+    }
+}
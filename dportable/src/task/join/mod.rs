@@ -0,0 +1,191 @@
+//! Portable task spawning and joining.
+
+use std::{
+    any::Any,
+    fmt::Display,
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures::{ready, FutureExt};
+
+#[cfg(not(target_arch = "wasm32"))]
+mod native;
+
+#[cfg(not(target_arch = "wasm32"))]
+use native as backend;
+
+#[cfg(target_arch = "wasm32")]
+mod wasm;
+
+#[cfg(target_arch = "wasm32")]
+use wasm as backend;
+
+/// Spawns a new asynchronous task, returning a [JoinHandle] for it.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn spawn<F>(future: F) -> JoinHandle<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    JoinHandle(backend::spawn(future))
+}
+
+/// Spawns a new asynchronous task, returning a [JoinHandle] for it.
+#[cfg(target_arch = "wasm32")]
+pub fn spawn<F>(future: F) -> JoinHandle<F::Output>
+where
+    F: Future + 'static,
+    F::Output: 'static,
+{
+    JoinHandle(backend::spawn(future))
+}
+
+/// Runs blocking closure without blocking async tasks, returning a [JoinHandle] for it.
+///
+/// On native platforms it runs on tokio's thread pool dedicated to blocking operations.
+/// In WASM there are no other threads, so it runs on the next microtask, blocking the thread.
+/// Blocking tasks can't be aborted once they started running.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn spawn_blocking<F, R>(f: F) -> JoinHandle<R>
+where
+    F: FnOnce() -> R + Send + 'static,
+    R: Send + 'static,
+{
+    JoinHandle(backend::spawn_blocking(f))
+}
+
+/// Runs blocking closure without blocking async tasks, returning a [JoinHandle] for it.
+///
+/// On native platforms it runs on tokio's thread pool dedicated to blocking operations.
+/// In WASM there are no other threads, so it runs on the next microtask, blocking the thread.
+/// Blocking tasks can't be aborted once they started running.
+#[cfg(target_arch = "wasm32")]
+pub fn spawn_blocking<F, R>(f: F) -> JoinHandle<R>
+where
+    F: FnOnce() -> R + 'static,
+    R: 'static,
+{
+    JoinHandle(backend::spawn_blocking(f))
+}
+
+/// Spawns a new non-[Send] asynchronous task on current thread, returning a [JoinHandle] for it.
+///
+/// On native platforms it must be called within [run_local].
+pub fn spawn_local<F>(future: F) -> JoinHandle<F::Output>
+where
+    F: Future + 'static,
+    F::Output: 'static,
+{
+    JoinHandle(backend::spawn_local(future))
+}
+
+/// Run future allowing it to use [spawn_local].
+///
+/// On native platforms local tasks still running when `future` completes are cancelled.
+pub async fn run_local<F>(future: F) -> F::Output
+where
+    F: Future,
+{
+    backend::run_local(future).await
+}
+
+/// Task failed to execute to completion.
+///
+/// In WASM tasks can't be unwound, so it can only be caused by cancellation there.
+#[derive(Debug)]
+pub struct JoinError(pub(super) backend::JoinError);
+
+impl JoinError {
+    /// Returns [true] if task was cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.is_cancelled()
+    }
+
+    /// Returns [true] if task panicked.
+    pub fn is_panic(&self) -> bool {
+        self.0.is_panic()
+    }
+
+    /// Consume the join error, returning the object with which task panicked.
+    ///
+    /// # Panics
+    ///
+    /// Panics if task didn't panic.
+    #[track_caller]
+    pub fn into_panic(self) -> Box<dyn Any + Send + 'static> {
+        self.try_into_panic()
+            .expect("`JoinError` reason is not a panic.")
+    }
+
+    /// Consume the join error, returning the object with which task panicked
+    /// or the error itself if task didn't panic.
+    pub fn try_into_panic(self) -> Result<Box<dyn Any + Send + 'static>, JoinError> {
+        self.0.try_into_panic().map_err(JoinError)
+    }
+}
+
+impl Display for JoinError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_panic() {
+            write!(f, "task panicked")
+        } else {
+            write!(f, "task was cancelled")
+        }
+    }
+}
+
+impl std::error::Error for JoinError {}
+
+/// An owned permission to join on a task (await its termination).
+///
+/// Dropping the handle detaches the task.
+#[derive(Debug)]
+pub struct JoinHandle<T>(backend::JoinHandle<T>);
+
+impl<T> JoinHandle<T> {
+    /// Abort the task.
+    ///
+    /// Awaiting the handle will most likely fail with cancelled [JoinError],
+    /// unless task already finished.
+    pub fn abort(&self) {
+        self.0.abort()
+    }
+
+    /// Returns [true] if task finished.
+    pub fn is_finished(&self) -> bool {
+        self.0.is_finished()
+    }
+
+    /// Return [AbortHandle] that can abort the task even after this handle is dropped.
+    pub fn abort_handle(&self) -> AbortHandle {
+        AbortHandle(self.0.abort_handle())
+    }
+}
+
+impl<T> Future for JoinHandle<T> {
+    type Output = Result<T, JoinError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        Poll::Ready(ready!(self.0.poll_unpin(cx)).map_err(JoinError))
+    }
+}
+
+/// An owned permission to abort a task, without awaiting its completion.
+///
+/// Unlike [JoinHandle], dropping it does nothing to the task.
+#[derive(Debug, Clone)]
+pub struct AbortHandle(pub(super) backend::AbortHandle);
+
+impl AbortHandle {
+    /// Abort the task.
+    pub fn abort(&self) {
+        self.0.abort()
+    }
+
+    /// Returns [true] if task finished (including being aborted).
+    pub fn is_finished(&self) -> bool {
+        self.0.is_finished()
+    }
+}
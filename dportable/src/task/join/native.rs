@@ -0,0 +1,12 @@
+use std::future::Future;
+
+pub(super) use tokio::task::{
+    spawn, spawn_blocking, spawn_local, AbortHandle, JoinError, JoinHandle,
+};
+
+pub(super) async fn run_local<F>(future: F) -> F::Output
+where
+    F: Future,
+{
+    tokio::task::LocalSet::new().run_until(future).await
+}
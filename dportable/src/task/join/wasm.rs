@@ -0,0 +1,116 @@
+use std::{
+    any::Any,
+    cell::Cell,
+    future::Future,
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll},
+};
+
+use futures::{
+    channel::oneshot::{channel, Receiver},
+    future::{self, Abortable, Aborted},
+    ready, FutureExt,
+};
+
+pub(crate) fn spawn<F>(future: F) -> JoinHandle<F::Output>
+where
+    F: Future + 'static,
+    F::Output: 'static,
+{
+    let (abort, registration) = future::AbortHandle::new_pair();
+    let finished = Rc::new(Cell::new(false));
+    let finished_clone = finished.clone();
+    let (sender, receiver) = channel();
+    wasm_bindgen_futures::spawn_local(async move {
+        // Aborting drops the future on its next poll.
+        let result = Abortable::new(future, registration).await;
+        finished_clone.set(true);
+        let _ = sender.send(result);
+    });
+    JoinHandle {
+        receiver,
+        abort_handle: AbortHandle { abort, finished },
+    }
+}
+
+pub(crate) use spawn as spawn_local;
+
+pub(crate) fn spawn_blocking<F, R>(f: F) -> JoinHandle<R>
+where
+    F: FnOnce() -> R + 'static,
+    R: 'static,
+{
+    spawn(async move { f() })
+}
+
+pub(crate) async fn run_local<F>(future: F) -> F::Output
+where
+    F: Future,
+{
+    future.await
+}
+
+#[derive(Debug)]
+pub(crate) struct JoinError;
+
+impl JoinError {
+    pub fn is_cancelled(&self) -> bool {
+        true
+    }
+
+    pub fn is_panic(&self) -> bool {
+        false
+    }
+
+    pub fn try_into_panic(self) -> Result<Box<dyn Any + Send + 'static>, JoinError> {
+        Err(self)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct AbortHandle {
+    abort: future::AbortHandle,
+    finished: Rc<Cell<bool>>,
+}
+
+impl AbortHandle {
+    pub fn abort(&self) {
+        self.abort.abort()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.finished.get()
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct JoinHandle<T> {
+    receiver: Receiver<Result<T, Aborted>>,
+    abort_handle: AbortHandle,
+}
+
+impl<T> JoinHandle<T> {
+    pub fn abort(&self) {
+        self.abort_handle.abort()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.abort_handle.is_finished()
+    }
+
+    pub fn abort_handle(&self) -> AbortHandle {
+        self.abort_handle.clone()
+    }
+}
+
+impl<T> Future for JoinHandle<T> {
+    type Output = Result<T, JoinError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match ready!(self.receiver.poll_unpin(cx)) {
+            Ok(Ok(value)) => Poll::Ready(Ok(value)),
+            Ok(Err(Aborted)) | Err(_) => Poll::Ready(Err(JoinError)),
+        }
+    }
+}
@@ -0,0 +1,16 @@
+//! Task-local storage.
+//!
+//! Values are bound to futures rather than tasks, so they are also accessible
+//! in WASM, where tasks are not tracked by the runtime.
+
+#[cfg(not(target_arch = "wasm32"))]
+mod native;
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use native::*;
+
+#[cfg(target_arch = "wasm32")]
+mod wasm;
+
+#[cfg(target_arch = "wasm32")]
+pub use wasm::*;
@@ -0,0 +1 @@
+pub use tokio::task::{futures::TaskLocalFuture, LocalKey};
@@ -0,0 +1,211 @@
+use std::{
+    cell::RefCell,
+    fmt::Debug,
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+    thread,
+};
+
+use error::AccessError;
+
+#[macro_export]
+macro_rules! task_local {
+    () => {};
+
+    ($(#[$attr:meta])* $vis:vis static $name:ident: $t:ty; $($rest:tt)*) => {
+        $crate::task_local!($(#[$attr])* $vis static $name: $t);
+        $crate::task_local!($($rest)*);
+    };
+
+    ($(#[$attr:meta])* $vis:vis static $name:ident: $t:ty) => {
+        $(#[$attr])*
+        $vis static $name: $crate::task::LocalKey<$t> = {
+            std::thread_local! {
+                static __KEY: std::cell::RefCell<Option<$t>> = const { std::cell::RefCell::new(None) };
+            }
+
+            $crate::task::LocalKey { inner: __KEY }
+        };
+    };
+}
+
+pub struct LocalKey<T: 'static> {
+    #[doc(hidden)]
+    pub inner: thread::LocalKey<RefCell<Option<T>>>,
+}
+
+impl<T: 'static> LocalKey<T> {
+    pub fn scope<F>(&'static self, value: T, f: F) -> TaskLocalFuture<T, F>
+    where
+        F: Future,
+    {
+        TaskLocalFuture {
+            local: self,
+            slot: Some(value),
+            future: Some(Box::pin(f)),
+        }
+    }
+
+    #[track_caller]
+    pub fn sync_scope<F, R>(&'static self, value: T, f: F) -> R
+    where
+        F: FnOnce() -> R,
+    {
+        let mut slot = Some(value);
+        match self.scope_inner(&mut slot, f) {
+            Ok(result) => result,
+            Err(_) => scope_borrowed(),
+        }
+    }
+
+    #[track_caller]
+    pub fn with<F, R>(&'static self, f: F) -> R
+    where
+        F: FnOnce(&T) -> R,
+    {
+        self.try_with(f)
+            .expect("cannot access a task-local storage value without setting it first")
+    }
+
+    pub fn try_with<F, R>(&'static self, f: F) -> Result<R, AccessError>
+    where
+        F: FnOnce(&T) -> R,
+    {
+        self.inner.with(|inner| match inner.try_borrow() {
+            Ok(value) => value.as_ref().map(f).ok_or(AccessError(())),
+            Err(_) => Err(AccessError(())),
+        })
+    }
+
+    /// Swap value in `slot` with the one in thread-local storage for the duration of `f`.
+    fn scope_inner<F, R>(&'static self, slot: &mut Option<T>, f: F) -> Result<R, AccessError>
+    where
+        F: FnOnce() -> R,
+    {
+        struct Guard<'a, T: 'static> {
+            local: &'static LocalKey<T>,
+            slot: &'a mut Option<T>,
+        }
+
+        impl<T: 'static> Drop for Guard<'_, T> {
+            fn drop(&mut self) {
+                self.local
+                    .inner
+                    .with(|inner| std::mem::swap(self.slot, &mut *inner.borrow_mut()));
+            }
+        }
+
+        self.inner.with(|inner| {
+            inner
+                .try_borrow_mut()
+                .map(|mut value| std::mem::swap(slot, &mut *value))
+                .map_err(|_| AccessError(()))
+        })?;
+        let _guard = Guard { local: self, slot };
+        Ok(f())
+    }
+}
+
+impl<T: Clone + 'static> LocalKey<T> {
+    #[track_caller]
+    pub fn get(&'static self) -> T {
+        self.with(|value| value.clone())
+    }
+
+    pub fn try_get(&'static self) -> Result<T, AccessError> {
+        self.try_with(|value| value.clone())
+    }
+}
+
+impl<T: 'static> Debug for LocalKey<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.pad("LocalKey { .. }")
+    }
+}
+
+#[track_caller]
+fn scope_borrowed() -> ! {
+    panic!("cannot enter a task-local scope while the task-local storage is borrowed")
+}
+
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct TaskLocalFuture<T: 'static, F> {
+    local: &'static LocalKey<T>,
+    slot: Option<T>,
+    future: Option<Pin<Box<F>>>,
+}
+
+// Value in slot is never pinned.
+impl<T: 'static, F> Unpin for TaskLocalFuture<T, F> {}
+
+impl<T: 'static, F> TaskLocalFuture<T, F> {
+    pub fn take_value(self: Pin<&mut Self>) -> Option<T> {
+        self.get_mut().slot.take()
+    }
+}
+
+impl<T: 'static, F> Future for TaskLocalFuture<T, F>
+where
+    F: Future,
+{
+    type Output = F::Output;
+
+    #[track_caller]
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let future = &mut this.future;
+        let result = this.local.scope_inner(&mut this.slot, || {
+            let poll = future
+                .as_mut()
+                .expect("`TaskLocalFuture` polled after completion")
+                .as_mut()
+                .poll(cx);
+            if poll.is_ready() {
+                *future = None;
+            }
+            poll
+        });
+        match result {
+            Ok(poll) => poll,
+            Err(_) => scope_borrowed(),
+        }
+    }
+}
+
+impl<T: 'static, F> Drop for TaskLocalFuture<T, F> {
+    fn drop(&mut self) {
+        if self.future.is_some() {
+            // Drop the future while the task-local is set, if possible.
+            let future = &mut self.future;
+            let _ = self.local.scope_inner(&mut self.slot, || *future = None);
+        }
+    }
+}
+
+impl<T: 'static, F> Debug for TaskLocalFuture<T, F>
+where
+    T: Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TaskLocalFuture")
+            .field("value", &self.slot)
+            .finish()
+    }
+}
+
+mod error {
+    use std::fmt::Display;
+
+    // Unnameable outside of this module, like its tokio counterpart.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct AccessError(pub(super) ());
+
+    impl Display for AccessError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "task-local value not set")
+        }
+    }
+
+    impl std::error::Error for AccessError {}
+}
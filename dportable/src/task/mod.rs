@@ -0,0 +1,338 @@
+//! Task management.
+
+mod coop;
+pub use coop::*;
+
+mod join;
+pub use join::*;
+
+mod local;
+pub use local::*;
+
+mod scope;
+pub use scope::*;
+
+#[cfg(not(target_arch = "wasm32"))]
+mod native;
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use native::*;
+
+#[cfg(target_arch = "wasm32")]
+mod wasm;
+
+#[cfg(target_arch = "wasm32")]
+pub use wasm::*;
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc, sync::Arc, time::Duration};
+
+    use futures::FutureExt;
+
+    use super::{run_local, scope, spawn, spawn_blocking, spawn_local, yield_now, Budget, JoinSet};
+    use crate::{
+        test::{dtest, dtest_configure},
+        time::{sleep, Instant},
+        Mutex,
+    };
+
+    dtest_configure!();
+
+    #[dtest]
+    async fn test_join_handle() {
+        let join_handle = spawn(async move { 4 });
+        sleep(Duration::from_millis(10)).await;
+        assert!(join_handle.is_finished());
+        assert_eq!(4, join_handle.await.unwrap());
+
+        let join_handle = spawn(sleep(Duration::from_secs(10)));
+        join_handle.abort();
+        let error = join_handle.await.unwrap_err();
+        assert!(error.is_cancelled());
+        assert!(!error.is_panic());
+        assert!(error.try_into_panic().is_err());
+    }
+
+    #[dtest]
+    async fn test_yield_now() {
+        let progress = Arc::new(Mutex::new(0));
+        let progress_clone = progress.clone();
+        // Bounded, as endless microtasks would keep timers in WASM from ever firing.
+        let join_handle = spawn(async move {
+            for _ in 0..1000 {
+                *progress_clone.lock() += 1;
+                yield_now().await;
+            }
+        });
+        yield_now().await;
+        assert!(*progress.lock() > 0);
+
+        let mut budget = Budget::new(10);
+        let before = *progress.lock();
+        for _ in 0..9 {
+            budget.tick().await;
+        }
+        assert_eq!(1, budget.remaining());
+        assert_eq!(before, *progress.lock());
+        for _ in 0..10 {
+            budget.tick().await;
+        }
+        assert!(*progress.lock() > before);
+        join_handle.abort();
+    }
+
+    #[dtest]
+    async fn test_budget_timers() {
+        let ticks = Arc::new(Mutex::new(0));
+        let ticks_clone = ticks.clone();
+        let join_handle = spawn(async move {
+            loop {
+                sleep(Duration::from_millis(1)).await;
+                *ticks_clone.lock() += 1;
+            }
+        });
+        let start = Instant::now();
+        let mut budget = Budget::new(100);
+        while *ticks.lock() < 3 {
+            assert!(start.elapsed() < Duration::from_secs(5), "timers starved");
+            budget.tick().await;
+        }
+        join_handle.abort();
+    }
+
+    crate::task_local! {
+        static ID: u32;
+    }
+
+    #[dtest]
+    async fn test_task_local() {
+        assert!(ID.try_get().is_err());
+        let join_handles: Vec<_> = [2, 1]
+            .into_iter()
+            .map(|id| {
+                spawn(ID.scope(id, async move {
+                    sleep(Duration::from_millis(10 * id as u64)).await;
+                    let nested = ID.scope(id + 10, async { ID.get() }).await;
+                    (ID.get(), nested)
+                }))
+            })
+            .collect();
+        for (join_handle, id) in join_handles.into_iter().zip([2, 1]) {
+            assert_eq!((id, id + 10), join_handle.await.unwrap());
+        }
+        assert_eq!(3, ID.sync_scope(3, || ID.with(|id| *id)));
+        assert!(ID.try_with(|id| *id).is_err());
+    }
+
+    #[dtest]
+    async fn test_spawn_blocking() {
+        let join_handle = spawn_blocking(|| (1..=10).sum::<u32>());
+        assert_eq!(55, join_handle.await.unwrap());
+
+        let value = Arc::new(Mutex::new(0));
+        let value_clone = value.clone();
+        spawn_blocking(move || *value_clone.lock() = 1)
+            .await
+            .unwrap();
+        assert_eq!(1, *value.lock());
+    }
+
+    #[dtest]
+    async fn test_spawn_local() {
+        run_local(async {
+            let value = Rc::new(RefCell::new(vec![]));
+            let join_handles: Vec<_> = [2, 1]
+                .into_iter()
+                .map(|i| {
+                    let value = value.clone();
+                    spawn_local(async move {
+                        sleep(Duration::from_millis(10 * i)).await;
+                        value.borrow_mut().push(i);
+                        i
+                    })
+                })
+                .collect();
+            for (join_handle, i) in join_handles.into_iter().zip([2, 1]) {
+                assert_eq!(i, join_handle.await.unwrap());
+            }
+            assert_eq!(vec![1, 2], *value.borrow());
+        })
+        .await;
+    }
+
+    struct SetOnDrop(Arc<Mutex<bool>>);
+
+    impl Drop for SetOnDrop {
+        fn drop(&mut self) {
+            *self.0.lock() = true;
+        }
+    }
+
+    #[dtest]
+    async fn test_abort() {
+        let dropped = Arc::new(Mutex::new(false));
+        let set_on_drop = SetOnDrop(dropped.clone());
+        let join_handle = spawn(async move {
+            let _set_on_drop = set_on_drop;
+            sleep(Duration::from_secs(10)).await;
+        });
+        sleep(Duration::from_millis(10)).await;
+        join_handle.abort();
+        assert!(join_handle.await.unwrap_err().is_cancelled());
+        assert!(*dropped.lock());
+    }
+
+    #[dtest]
+    async fn test_abort_handle() {
+        let dropped = Arc::new(Mutex::new(false));
+        let set_on_drop = SetOnDrop(dropped.clone());
+        let join_handle = spawn(async move {
+            let _set_on_drop = set_on_drop;
+            sleep(Duration::from_secs(10)).await;
+        });
+        let abort_handle = join_handle.abort_handle();
+        drop(join_handle);
+        sleep(Duration::from_millis(10)).await;
+        assert!(!abort_handle.is_finished());
+        abort_handle.abort();
+        sleep(Duration::from_millis(10)).await;
+        assert!(abort_handle.is_finished());
+        assert!(*dropped.lock());
+
+        let mut join_set = JoinSet::new();
+        let abort_handle = join_set.spawn(sleep(Duration::from_secs(10)));
+        abort_handle.abort();
+        assert!(join_set
+            .join_next()
+            .await
+            .unwrap()
+            .unwrap_err()
+            .is_cancelled());
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[dtest]
+    async fn test_join_handle_panic() {
+        let error = spawn(async move { panic!("boom") }).await.unwrap_err();
+        assert!(error.is_panic());
+        assert!(!error.is_cancelled());
+        assert_eq!(&"boom", error.into_panic().downcast_ref::<&str>().unwrap());
+    }
+
+    #[dtest]
+    async fn test_join_set() {
+        let mut join_set = JoinSet::new();
+        assert!(join_set.join_next().await.is_none());
+        for i in [3, 1, 2] {
+            join_set.spawn(async move {
+                sleep(Duration::from_millis(10 * i)).await;
+                i
+            });
+        }
+        assert_eq!(3, join_set.len());
+
+        let mut results = vec![];
+        while let Some(result) = join_set.join_next().await {
+            results.push(result.unwrap());
+        }
+        assert_eq!(vec![1, 2, 3], results);
+        assert!(join_set.is_empty());
+    }
+
+    #[dtest]
+    async fn test_join_set_abort() {
+        let finished = Arc::new(Mutex::new(0));
+        let mut join_set = JoinSet::new();
+        for _ in 0..2 {
+            let finished = finished.clone();
+            join_set.spawn(async move {
+                sleep(Duration::from_millis(20)).await;
+                *finished.lock() += 1;
+            });
+        }
+        join_set.abort_all();
+        while let Some(result) = join_set.join_next().await {
+            assert!(result.unwrap_err().is_cancelled());
+        }
+
+        let finished_clone = finished.clone();
+        join_set.spawn(async move {
+            sleep(Duration::from_millis(20)).await;
+            *finished_clone.lock() += 1;
+        });
+        drop(join_set);
+
+        sleep(Duration::from_millis(40)).await;
+        assert_eq!(0, *finished.lock());
+    }
+
+    #[dtest]
+    async fn test_scope() {
+        let finished = Arc::new(Mutex::new(0));
+        let result = scope(|s| {
+            let finished = finished.clone();
+            async move {
+                for i in 1..=3 {
+                    let finished = finished.clone();
+                    let s_clone = s.clone();
+                    s.spawn(async move {
+                        sleep(Duration::from_millis(10 * i)).await;
+                        let finished = finished.clone();
+                        s_clone.spawn(async move {
+                            sleep(Duration::from_millis(10)).await;
+                            *finished.lock() += 1;
+                            Ok(())
+                        });
+                        Ok::<_, ()>(())
+                    });
+                }
+                Ok(5)
+            }
+        })
+        .await;
+        assert_eq!(Ok(5), result);
+        assert_eq!(3, *finished.lock());
+    }
+
+    #[dtest]
+    async fn test_scope_error() {
+        let dropped = Arc::new(Mutex::new(false));
+        let set_on_drop = SetOnDrop(dropped.clone());
+        let result: Result<(), _> = scope(|s| async move {
+            s.spawn(async move {
+                let _set_on_drop = set_on_drop;
+                sleep(Duration::from_secs(10)).await;
+                Ok(())
+            });
+            s.spawn(async move {
+                sleep(Duration::from_millis(10)).await;
+                Err("failed")
+            });
+            sleep(Duration::from_secs(10)).await;
+            Ok(())
+        })
+        .await;
+        assert_eq!(Err("failed"), result);
+        assert!(*dropped.lock());
+
+        let result: Result<(), _> = scope(|s| async move {
+            s.spawn(sleep(Duration::from_secs(10)).map(Ok));
+            Err("failed")
+        })
+        .await;
+        assert_eq!(Err("failed"), result);
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[dtest]
+    async fn test_scope_panic() {
+        let error = spawn(scope(|s| async move {
+            s.spawn(async move { panic!("boom") });
+            Ok::<_, ()>(())
+        }))
+        .await
+        .unwrap_err();
+        assert_eq!(&"boom", error.into_panic().downcast_ref::<&str>().unwrap());
+    }
+}
@@ -0,0 +1,58 @@
+use std::future::Future;
+
+use super::{AbortHandle, JoinError};
+
+#[derive(Debug)]
+pub struct JoinSet<T>(tokio::task::JoinSet<T>);
+
+impl<T> JoinSet<T> {
+    pub fn new() -> Self {
+        JoinSet(tokio::task::JoinSet::new())
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl<T> JoinSet<T>
+where
+    T: 'static,
+{
+    pub fn abort_all(&mut self) {
+        self.0.abort_all()
+    }
+
+    pub async fn join_next(&mut self) -> Option<Result<T, JoinError>> {
+        self.0
+            .join_next()
+            .await
+            .map(|result| result.map_err(JoinError))
+    }
+
+    pub async fn shutdown(&mut self) {
+        self.0.shutdown().await
+    }
+}
+
+impl<T> JoinSet<T>
+where
+    T: Send + 'static,
+{
+    pub fn spawn<F>(&mut self, task: F) -> AbortHandle
+    where
+        F: Future<Output = T> + Send + 'static,
+    {
+        AbortHandle(self.0.spawn(task))
+    }
+}
+
+impl<T> Default for JoinSet<T> {
+    fn default() -> Self {
+        JoinSet::new()
+    }
+}
@@ -0,0 +1,165 @@
+//! Structured concurrency.
+
+use std::{
+    any::Any,
+    future::Future,
+    pin::pin,
+    task::{ready, Context, Poll},
+};
+
+use futures::{
+    channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender},
+    future::{poll_fn, Fuse, FusedFuture},
+    stream::FuturesUnordered,
+    FutureExt, StreamExt,
+};
+
+use super::{spawn, JoinHandle};
+use crate::sync::CancellationToken;
+
+/// Handle for spawning tasks within [scope].
+#[derive(Debug)]
+pub struct Scope<E> {
+    sender: UnboundedSender<JoinHandle<Result<(), E>>>,
+    token: CancellationToken,
+}
+
+impl<E> Scope<E> {
+    /// Returns [true] if scope is cancelled, because one of its tasks failed.
+    pub fn is_cancelled(&self) -> bool {
+        self.token.is_cancelled()
+    }
+
+    fn spawned(&self, join_handle: JoinHandle<Result<(), E>>) {
+        // Scope already completed, its cancelled token prevents task from running.
+        let _ = self.sender.unbounded_send(join_handle);
+    }
+}
+
+impl<E> Scope<E>
+where
+    E: 'static,
+{
+    /// Spawn task within scope.
+    ///
+    /// Task is cancelled if any other task of the scope fails.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn spawn<F>(&self, future: F)
+    where
+        F: Future<Output = Result<(), E>> + Send + 'static,
+        E: Send,
+    {
+        let token = self.token.clone();
+        self.spawned(spawn(async move {
+            token.run_until_cancelled(future).await.unwrap_or(Ok(()))
+        }));
+    }
+
+    /// Spawn task within scope.
+    ///
+    /// Task is cancelled if any other task of the scope fails.
+    #[cfg(target_arch = "wasm32")]
+    pub fn spawn<F>(&self, future: F)
+    where
+        F: Future<Output = Result<(), E>> + 'static,
+    {
+        let token = self.token.clone();
+        self.spawned(spawn(async move {
+            token.run_until_cancelled(future).await.unwrap_or(Ok(()))
+        }));
+    }
+}
+
+impl<E> Clone for Scope<E> {
+    fn clone(&self) -> Self {
+        Scope {
+            sender: self.sender.clone(),
+            token: self.token.clone(),
+        }
+    }
+}
+
+enum Failure<E> {
+    Error(E),
+    Panic(Box<dyn Any + Send + 'static>),
+}
+
+/// Run `f` with [Scope] for spawning tasks, waiting for all of them to finish.
+///
+/// First error returned by `f` or any spawned task cancels all remaining tasks
+/// and is returned once they finish. Panics of spawned tasks are propagated likewise.
+/// Dropping returned future cancels all tasks.
+pub async fn scope<F, Fut, T, E>(f: F) -> Result<T, E>
+where
+    F: FnOnce(Scope<E>) -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+{
+    let (sender, mut receiver) = unbounded();
+    let token = CancellationToken::new();
+    let _guard = token.clone().drop_guard();
+    let mut body = pin!(f(Scope {
+        sender,
+        token: token.clone()
+    })
+    .fuse());
+    let mut tasks = FuturesUnordered::new();
+    let mut output = None;
+    let mut failure = None;
+
+    poll_fn(|cx| {
+        if !body.is_terminated() {
+            if let Poll::Ready(result) = body.as_mut().poll(cx) {
+                match result {
+                    Ok(value) => output = Some(value),
+                    Err(error) => failure = Some(Failure::Error(error)),
+                }
+            }
+        }
+        loop {
+            receive(&mut receiver, &mut tasks, cx);
+            if failure.is_some() && !token.is_cancelled() {
+                token.cancel();
+                body.set(Fuse::terminated());
+            }
+            match ready!(tasks.poll_next_unpin(cx)) {
+                Some(Ok(Ok(()))) => (),
+                Some(Ok(Err(error))) => {
+                    failure.get_or_insert(Failure::Error(error));
+                }
+                Some(Err(error)) => {
+                    if error.is_panic() {
+                        failure.get_or_insert(Failure::Panic(error.into_panic()));
+                    }
+                }
+                None => {
+                    // Finished tasks may have spawned new ones before exiting.
+                    receive(&mut receiver, &mut tasks, cx);
+                    if tasks.is_empty() {
+                        return if body.is_terminated() {
+                            Poll::Ready(())
+                        } else {
+                            Poll::Pending
+                        };
+                    }
+                }
+            }
+        }
+    })
+    .await;
+
+    match failure {
+        Some(Failure::Error(error)) => Err(error),
+        Some(Failure::Panic(payload)) => std::panic::resume_unwind(payload),
+        None => Ok(output.expect("scope body finished")),
+    }
+}
+
+fn receive<E>(
+    receiver: &mut UnboundedReceiver<JoinHandle<Result<(), E>>>,
+    tasks: &mut FuturesUnordered<JoinHandle<Result<(), E>>>,
+    cx: &mut Context<'_>,
+) {
+    while let Poll::Ready(Some(join_handle)) = receiver.poll_next_unpin(cx) {
+        tasks.push(join_handle);
+    }
+}
@@ -0,0 +1,80 @@
+use std::{future::Future, task::Poll};
+
+use futures::{future::poll_fn, FutureExt};
+
+use super::{spawn, AbortHandle, JoinError, JoinHandle};
+
+#[derive(Debug)]
+pub struct JoinSet<T> {
+    handles: Vec<JoinHandle<T>>,
+}
+
+impl<T> JoinSet<T> {
+    pub fn new() -> Self {
+        JoinSet {
+            handles: Vec::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.handles.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.handles.is_empty()
+    }
+
+    pub fn abort_all(&mut self) {
+        for handle in &self.handles {
+            handle.abort();
+        }
+    }
+
+    pub async fn join_next(&mut self) -> Option<Result<T, JoinError>> {
+        poll_fn(|cx| {
+            if self.handles.is_empty() {
+                return Poll::Ready(None);
+            }
+            for index in 0..self.handles.len() {
+                if let Poll::Ready(result) = self.handles[index].poll_unpin(cx) {
+                    self.handles.swap_remove(index);
+                    return Poll::Ready(Some(result));
+                }
+            }
+            Poll::Pending
+        })
+        .await
+    }
+
+    pub async fn shutdown(&mut self) {
+        self.abort_all();
+        while self.join_next().await.is_some() {}
+    }
+}
+
+impl<T> JoinSet<T>
+where
+    T: 'static,
+{
+    pub fn spawn<F>(&mut self, task: F) -> AbortHandle
+    where
+        F: Future<Output = T> + 'static,
+    {
+        let handle = spawn(task);
+        let abort_handle = handle.abort_handle();
+        self.handles.push(handle);
+        abort_handle
+    }
+}
+
+impl<T> Default for JoinSet<T> {
+    fn default() -> Self {
+        JoinSet::new()
+    }
+}
+
+impl<T> Drop for JoinSet<T> {
+    fn drop(&mut self) {
+        self.abort_all();
+    }
+}
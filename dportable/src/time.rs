@@ -53,6 +53,12 @@ impl Timeout {
     }
 }
 
+impl From<Duration> for Timeout {
+    fn from(duration: Duration) -> Self {
+        Timeout::new(duration)
+    }
+}
+
 impl Future for Timeout {
     type Output = ();
 
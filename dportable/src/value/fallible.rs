@@ -0,0 +1,242 @@
+//! Async value that can be set only once, either with success or failure.
+
+use std::{
+    fmt::Display,
+    future::{Future, IntoFuture},
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use futures::{future::FusedFuture, ready, FutureExt};
+
+use super::{AlreadySet, Notifier};
+use crate::Mutex;
+
+/// All handles were dropped or awaited without setting value error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Closed {}
+
+impl Display for Closed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "value closed without being set")
+    }
+}
+
+impl std::error::Error for Closed {}
+
+/// Async value that can be set only once, either with success or failure.
+///
+/// Awaiting a handle turns it into a waiting future, so it can no longer set the value.
+/// Once every handle is either dropped or awaited without value being set,
+/// waiting futures resolve with [Closed] error converted into `E`.
+#[derive(Debug)]
+pub struct TryAsyncValue<T, E> {
+    inner: Arc<Inner<T, E>>,
+}
+
+#[derive(Debug)]
+struct Inner<T, E> {
+    state: Mutex<State<T, E>>,
+    notifier: Notifier,
+}
+
+#[derive(Debug)]
+struct State<T, E> {
+    result: Option<Result<T, E>>,
+    setters: usize,
+}
+
+impl<T, E> TryAsyncValue<T, E> {
+    /// Create new fallible async value.
+    pub fn new() -> Self {
+        TryAsyncValue {
+            inner: Arc::new(Inner {
+                state: Mutex::new(State {
+                    result: None,
+                    setters: 1,
+                }),
+                notifier: Notifier::new(),
+            }),
+        }
+    }
+
+    /// Set successful value.
+    pub fn set_ok(&self, value: T) -> Result<(), AlreadySet> {
+        self.set(Ok(value))
+    }
+
+    /// Set failure.
+    pub fn set_err(&self, error: E) -> Result<(), AlreadySet> {
+        self.set(Err(error))
+    }
+
+    /// Set result.
+    pub fn set(&self, result: Result<T, E>) -> Result<(), AlreadySet> {
+        let mut state = self.inner.state.lock();
+        if state.result.is_some() {
+            Err(AlreadySet {})
+        } else {
+            state.result = Some(result);
+            self.inner.notifier.notify();
+            Ok(())
+        }
+    }
+
+    /// Returns [true] if value was already set.
+    pub fn is_set(&self) -> bool {
+        self.inner.state.lock().result.is_some()
+    }
+}
+
+impl<T, E> TryAsyncValue<T, E>
+where
+    T: Clone,
+    E: Clone,
+{
+    /// Return result or [None] if not yet set.
+    pub fn try_get(&self) -> Option<Result<T, E>> {
+        self.inner.state.lock().result.clone()
+    }
+}
+
+impl<T, E> Clone for TryAsyncValue<T, E> {
+    fn clone(&self) -> Self {
+        self.inner.state.lock().setters += 1;
+        TryAsyncValue {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T, E> Default for TryAsyncValue<T, E> {
+    fn default() -> Self {
+        TryAsyncValue::new()
+    }
+}
+
+impl<T, E> Drop for TryAsyncValue<T, E> {
+    fn drop(&mut self) {
+        let mut state = self.inner.state.lock();
+        state.setters -= 1;
+        if state.setters == 0 {
+            // Wake waiting futures, they will resolve with `Closed` if value wasn't set.
+            self.inner.notifier.notify();
+        }
+    }
+}
+
+impl<T, E> IntoFuture for TryAsyncValue<T, E>
+where
+    T: Clone,
+    E: Clone + From<Closed>,
+{
+    type Output = Result<T, E>;
+    type IntoFuture = TryAsyncValueFuture<T, E>;
+
+    fn into_future(self) -> Self::IntoFuture {
+        TryAsyncValueFuture {
+            inner: self.inner.clone(),
+            notifier: self.inner.notifier.clone(),
+            terminated: false,
+        }
+    }
+}
+
+/// Future waiting for [TryAsyncValue] to be set.
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct TryAsyncValueFuture<T, E> {
+    inner: Arc<Inner<T, E>>,
+    notifier: Notifier,
+    terminated: bool,
+}
+
+impl<T, E> Future for TryAsyncValueFuture<T, E>
+where
+    T: Clone,
+    E: Clone + From<Closed>,
+{
+    type Output = Result<T, E>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        ready!(self.notifier.poll_unpin(cx));
+        self.terminated = true;
+        let state = self.inner.state.lock();
+        Poll::Ready(match &state.result {
+            Some(result) => result.clone(),
+            None => Err(Closed {}.into()),
+        })
+    }
+}
+
+impl<T, E> FusedFuture for TryAsyncValueFuture<T, E>
+where
+    T: Clone,
+    E: Clone + From<Closed>,
+{
+    fn is_terminated(&self) -> bool {
+        self.terminated
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{future::IntoFuture, time::Duration};
+
+    use crate::test::{dtest, dtest_configure};
+    use crate::value::AlreadySet;
+    use crate::{spawn, time::sleep};
+
+    use super::{Closed, TryAsyncValue};
+
+    dtest_configure!();
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    enum Error {
+        Failed,
+        Closed,
+    }
+
+    impl From<Closed> for Error {
+        fn from(_: Closed) -> Self {
+            Error::Closed
+        }
+    }
+
+    #[dtest]
+    async fn test_try_async_value() {
+        let value = TryAsyncValue::<u32, Error>::new();
+        let join_handle = spawn(value.clone().into_future());
+        assert_eq!(value.try_get(), None);
+        value.set_ok(5).unwrap();
+        assert_eq!(value.set_err(Error::Failed), Err(AlreadySet {}));
+        assert_eq!(value.try_get(), Some(Ok(5)));
+        assert_eq!(join_handle.await.unwrap(), Ok(5));
+        assert_eq!(value.await, Ok(5));
+
+        let value = TryAsyncValue::<u32, Error>::new();
+        let value_clone = value.clone();
+        spawn(async move {
+            sleep(Duration::from_millis(10)).await;
+            value_clone.set_err(Error::Failed).unwrap();
+        });
+        assert_eq!(value.await, Err(Error::Failed));
+    }
+
+    #[dtest]
+    async fn test_try_async_value_closed() {
+        let value = TryAsyncValue::<u32, Error>::new();
+        let value_clone = value.clone();
+        spawn(async move {
+            sleep(Duration::from_millis(10)).await;
+            drop(value_clone);
+        });
+        assert_eq!(value.await, Err(Error::Closed));
+
+        let value = TryAsyncValue::<u32, Error>::new();
+        let join_handle = spawn(value.clone().into_future());
+        assert_eq!(value.await, Err(Error::Closed));
+        assert_eq!(join_handle.await.unwrap(), Err(Error::Closed));
+    }
+}
@@ -122,7 +122,7 @@ mod tests {
     async fn test_async_value() {
         let value = AsyncValue::new();
         let value_clone = value.clone();
-        let join_handle = spawn(async move { value_clone.await });
+        let join_handle = spawn(value_clone);
         assert_eq!(value.try_get(), None);
         value.set(5).unwrap();
         assert_eq!(value.set(1), Err(AlreadySet {}));
@@ -134,7 +134,7 @@ mod tests {
     async fn test_notifier() {
         let notifier = Notifier::new();
         let notifier_clone = notifier.clone();
-        let join_handle = spawn(async move { notifier_clone.await });
+        let join_handle = spawn(notifier_clone);
         let notifier_clone = notifier.clone();
         spawn(async move {
             sleep(Duration::from_millis(10)).await;
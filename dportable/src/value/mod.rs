@@ -6,8 +6,14 @@
 mod immutable;
 pub use immutable::*;
 
+mod fallible;
+pub use fallible::*;
+
 pub mod mutable;
 
+mod watch;
+pub use watch::*;
+
 use std::fmt::Display;
 
 /// Value was already set error.
@@ -1,4 +1,4 @@
-//! Async value that can be reset to empty state.
+//! Async value and notifier that can be reset to empty state.
 
 use std::{
     future::Future,
@@ -8,9 +8,14 @@ use std::{
 };
 
 use futures::{
-    channel::oneshot::{channel, Receiver, Sender},
+    channel::{
+        mpsc::{unbounded, UnboundedReceiver, UnboundedSender},
+        oneshot::{channel, Receiver, Sender},
+    },
     future::Shared,
-    ready, FutureExt,
+    ready,
+    stream::FusedStream,
+    FutureExt, Stream, StreamExt,
 };
 
 use crate::{Mutex, RwLock};
@@ -21,17 +26,27 @@ use super::AlreadySet;
 #[derive(Debug)]
 pub struct AsyncValue<T> {
     value: Arc<RwLock<Option<T>>>,
-    sender: Arc<Mutex<Option<Sender<T>>>>,
-    receiver: Arc<Mutex<Shared<Receiver<T>>>>,
+    generation: Arc<Mutex<Generation<T>>>,
+    /// Receiver of generation this handle is waiting on, if it was polled.
+    waiting: Option<Shared<Receiver<T>>>,
+}
+
+/// Channel of current generation, replaced once value is taken.
+#[derive(Debug)]
+struct Generation<T> {
+    /// [None] once value is set.
+    sender: Option<Sender<T>>,
+    receiver: Shared<Receiver<T>>,
+    /// Senders of streams returned by [AsyncValue::changes].
+    subscribers: Vec<UnboundedSender<T>>,
 }
 
 impl<T> Clone for AsyncValue<T> {
     fn clone(&self) -> Self {
-        let receiver = Arc::new(Mutex::new(self.receiver.lock().clone()));
         Self {
             value: self.value.clone(),
-            sender: self.sender.clone(),
-            receiver,
+            generation: self.generation.clone(),
+            waiting: None,
         }
     }
 }
@@ -44,24 +59,30 @@ where
     pub fn new() -> Self {
         let (sender, receiver) = channel();
         let value = Arc::new(RwLock::new(None));
-        let sender = Arc::new(Mutex::new(Some(sender)));
-        let receiver = Arc::new(Mutex::new(receiver.shared()));
+        let generation = Arc::new(Mutex::new(Generation {
+            sender: Some(sender),
+            receiver: receiver.shared(),
+            subscribers: vec![],
+        }));
         AsyncValue {
             value,
-            sender,
-            receiver,
+            generation,
+            waiting: None,
         }
     }
 
     /// Set value.
     pub fn set(&self, new_value: T) -> Result<(), AlreadySet> {
         let mut value = self.value.write();
-        let mut sender = self.sender.lock();
+        let mut generation = self.generation.lock();
         if value.is_some() {
             Err(AlreadySet {})
         } else {
             *value = Some(new_value.clone());
-            let _ = sender.take().unwrap().send(new_value);
+            generation
+                .subscribers
+                .retain(|subscriber| subscriber.unbounded_send(new_value.clone()).is_ok());
+            let _ = generation.sender.take().unwrap().send(new_value);
             Ok(())
         }
     }
@@ -71,14 +92,12 @@ where
     /// It will reset this async value to empty state.
     pub fn take(&self) -> Option<T> {
         let mut value = self.value.write();
-        let mut sender = self.sender.lock();
-        let mut receiver = self.receiver.lock();
+        let mut generation = self.generation.lock();
         let result = value.take();
         if result.is_some() {
-            let (new_sender, new_receiver) = channel();
-            let new_receiver = new_receiver.shared();
-            *sender = Some(new_sender);
-            *receiver = new_receiver;
+            let (sender, receiver) = channel();
+            generation.sender = Some(sender);
+            generation.receiver = receiver.shared();
         }
         result
     }
@@ -87,6 +106,20 @@ where
     pub fn try_get(&self) -> Option<T> {
         self.value.read().clone()
     }
+
+    /// Return stream of values, yielding every value set from now on.
+    ///
+    /// It starts with current value, if it is set. Values are buffered until
+    /// the stream is polled. Stream ends once all handles to this async value are dropped.
+    pub fn changes(&self) -> Changes<T> {
+        let value = self.value.read();
+        let (sender, receiver) = unbounded();
+        if let Some(value) = &*value {
+            let _ = sender.unbounded_send(value.clone());
+        }
+        self.generation.lock().subscribers.push(sender);
+        Changes(receiver)
+    }
 }
 
 impl<T> Default for AsyncValue<T>
@@ -104,24 +137,150 @@ where
 {
     type Output = T;
 
-    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
-        match &*self.value.read() {
-            Some(value) => Poll::Ready(value.clone()),
-            None => match ready!(self.receiver.lock().poll_unpin(cx)) {
-                Ok(value) => Poll::Ready(value),
-                Err(_) => Poll::Pending,
-            },
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if self.waiting.is_none() {
+            if let Some(value) = &*self.value.read() {
+                return Poll::Ready(value.clone());
+            }
+            let receiver = self.generation.lock().receiver.clone();
+            self.waiting = Some(receiver);
+        }
+        // Value set while waiting is returned even if it was already taken.
+        let result = ready!(self.waiting.as_mut().unwrap().poll_unpin(cx));
+        match result {
+            Ok(value) => {
+                self.waiting = None;
+                Poll::Ready(value)
+            }
+            Err(_) => Poll::Pending,
+        }
+    }
+}
+
+/// Stream returned by [AsyncValue::changes].
+#[derive(Debug)]
+#[must_use = "streams do nothing unless polled"]
+pub struct Changes<T>(UnboundedReceiver<T>);
+
+impl<T> Stream for Changes<T> {
+    type Item = T;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.0.poll_next_unpin(cx)
+    }
+}
+
+impl<T> FusedStream for Changes<T> {
+    fn is_terminated(&self) -> bool {
+        self.0.is_terminated()
+    }
+}
+
+/// Asynchronous notifier that can be reset and notified again.
+///
+/// Every reset starts a new generation, waiters always wake up once the generation
+/// they were waiting for is notified - even if notifier was reset again right after.
+#[derive(Debug, Clone)]
+pub struct Notifier {
+    inner: Arc<Mutex<NotifierInner>>,
+}
+
+#[derive(Debug)]
+struct NotifierInner {
+    generation: u64,
+    sender: Option<Sender<u64>>,
+    receiver: Shared<Receiver<u64>>,
+}
+
+impl Notifier {
+    /// Create new resettable async notifier.
+    pub fn new() -> Self {
+        let (sender, receiver) = channel();
+        let inner = NotifierInner {
+            generation: 0,
+            sender: Some(sender),
+            receiver: receiver.shared(),
+        };
+        Notifier {
+            inner: Arc::new(Mutex::new(inner)),
+        }
+    }
+
+    /// Notify waiters of current generation.
+    pub fn notify(&self) {
+        let mut inner = self.inner.lock();
+        if let Some(sender) = inner.sender.take() {
+            let _ = sender.send(inner.generation);
+        }
+    }
+
+    /// Reset notifier to not notified state, starting new generation.
+    ///
+    /// Does nothing if current generation wasn't notified yet.
+    pub fn reset(&self) {
+        let mut inner = self.inner.lock();
+        if inner.sender.is_none() {
+            let (sender, receiver) = channel();
+            inner.generation += 1;
+            inner.sender = Some(sender);
+            inner.receiver = receiver.shared();
+        }
+    }
+
+    /// Returns [true] if current generation was already notified.
+    pub fn is_set(&self) -> bool {
+        self.inner.lock().sender.is_none()
+    }
+
+    /// Return current generation number.
+    pub fn generation(&self) -> u64 {
+        self.inner.lock().generation
+    }
+
+    /// Wait for current generation to be notified.
+    ///
+    /// Returned future resolves to number of notified generation.
+    pub fn notified(&self) -> Notified {
+        Notified(self.inner.lock().receiver.clone())
+    }
+}
+
+impl Default for Notifier {
+    fn default() -> Self {
+        Notifier::new()
+    }
+}
+
+/// Future returned by [Notifier::notified].
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Notified(Shared<Receiver<u64>>);
+
+impl Future for Notified {
+    type Output = u64;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match ready!(self.0.poll_unpin(cx)) {
+            Ok(generation) => Poll::Ready(generation),
+            Err(_) => Poll::Pending,
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::spawn;
-    use crate::test::{dtest, dtest_configure};
-    use crate::value::AlreadySet;
+    use std::{sync::Arc, time::Duration};
+
+    use futures::{FutureExt, StreamExt};
 
-    use super::AsyncValue;
+    use crate::{
+        spawn,
+        test::{dtest, dtest_configure},
+        time::sleep,
+        value::AlreadySet,
+    };
+
+    use super::{AsyncValue, Notifier};
 
     dtest_configure!();
 
@@ -129,7 +288,7 @@ mod tests {
     async fn test_async_value() {
         let value = AsyncValue::new();
         let value_clone = value.clone();
-        let join_handle = spawn(async move { value_clone.await });
+        let join_handle = spawn(value_clone);
         assert_eq!(value.try_get(), None);
         value.set(5).unwrap();
         assert_eq!(value.set(1), Err(AlreadySet {}));
@@ -147,4 +306,85 @@ mod tests {
         value.set(5).unwrap();
         assert_eq!(value.await, 5);
     }
+
+    #[dtest]
+    async fn test_take_clone() {
+        let value = AsyncValue::new();
+        let mut value_clone = value.clone();
+        value.set(1).unwrap();
+        assert_eq!(value.take(), Some(1));
+        assert!((&mut value_clone).now_or_never().is_none());
+        value.set(2).unwrap();
+        assert_eq!(value_clone.await, 2);
+    }
+
+    #[dtest]
+    async fn test_take_pending_clone() {
+        let value = AsyncValue::new();
+        let join_handle = spawn(value.clone());
+        sleep(Duration::from_millis(10)).await;
+        value.set(1).unwrap();
+        assert_eq!(value.take(), Some(1));
+        assert_eq!(join_handle.await.unwrap(), 1);
+    }
+
+    #[dtest]
+    async fn test_take_releases_values() {
+        let value = AsyncValue::new();
+        let value_clone = value.clone();
+        let changes = value.changes();
+        drop(changes);
+        let tracked = Arc::new(());
+        for _ in 0..100 {
+            value.set(tracked.clone()).unwrap();
+            value.take();
+        }
+        assert_eq!(1, Arc::strong_count(&tracked));
+        drop(value_clone);
+    }
+
+    #[dtest]
+    async fn test_changes() {
+        let value = AsyncValue::new();
+        value.set(0).unwrap();
+        let changes = value.changes();
+        let value_clone = value.clone();
+        let join_handle = spawn(async move {
+            for i in 1..=3 {
+                sleep(Duration::from_millis(10)).await;
+                value_clone.take();
+                value_clone.set(i).unwrap();
+            }
+            value_clone.take();
+            value_clone.set(4).unwrap();
+        });
+        join_handle.await.unwrap();
+        assert_eq!(value.try_get(), Some(4));
+        drop(value);
+        assert_eq!(vec![0, 1, 2, 3, 4], changes.collect::<Vec<_>>().await);
+    }
+
+    #[dtest]
+    async fn test_notifier() {
+        let notifier = Notifier::new();
+        assert!(!notifier.is_set());
+        let notified = notifier.notified();
+        let notifier_clone = notifier.clone();
+        let join_handle = spawn(async move {
+            sleep(Duration::from_millis(10)).await;
+            notifier_clone.notify();
+            notifier_clone.reset();
+        });
+        assert_eq!(0, notified.await);
+        join_handle.await.unwrap();
+
+        assert!(!notifier.is_set());
+        assert_eq!(1, notifier.generation());
+        assert!(notifier.notified().now_or_never().is_none());
+        notifier.reset();
+        assert_eq!(1, notifier.generation());
+        notifier.notify();
+        assert!(notifier.is_set());
+        assert_eq!(1, notifier.notified().await);
+    }
 }
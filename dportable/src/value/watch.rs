@@ -0,0 +1,202 @@
+//! Observable value.
+
+use std::sync::Arc;
+
+use futures::{stream, Stream};
+
+use super::mutable::Notifier;
+use crate::{MappedRwLockReadGuard, RwLock, RwLockReadGuard};
+
+/// Observable value, notifying about every change.
+///
+/// Every clone keeps track of the latest version it has seen, so that [Watch::changed]
+/// resolves only for changes that weren't observed by that clone yet.
+#[derive(Debug)]
+pub struct Watch<T> {
+    shared: Arc<Shared<T>>,
+    seen: u64,
+}
+
+#[derive(Debug)]
+struct Shared<T> {
+    state: RwLock<State<T>>,
+    notifier: Notifier,
+}
+
+#[derive(Debug)]
+struct State<T> {
+    value: T,
+    version: u64,
+}
+
+impl<T> Watch<T> {
+    /// Create new observable value.
+    pub fn new(value: T) -> Self {
+        Watch {
+            shared: Arc::new(Shared {
+                state: RwLock::new(State { value, version: 0 }),
+                notifier: Notifier::new(),
+            }),
+            seen: 0,
+        }
+    }
+
+    /// Replace value, notifying observers.
+    pub fn send(&self, value: T) {
+        self.send_modify(|current| *current = value);
+    }
+
+    /// Modify value in place, notifying observers.
+    pub fn send_modify<F>(&self, modify: F)
+    where
+        F: FnOnce(&mut T),
+    {
+        {
+            let mut state = self.shared.state.write();
+            modify(&mut state.value);
+            state.version += 1;
+        }
+        self.shared.notifier.notify();
+        self.shared.notifier.reset();
+    }
+
+    /// Borrow current value.
+    ///
+    /// Guard must not be held across `.await` points - sending would deadlock
+    /// (or panic in WASM).
+    pub fn borrow(&self) -> MappedRwLockReadGuard<'_, T> {
+        RwLockReadGuard::map(self.shared.state.read(), |state| &state.value)
+    }
+
+    /// Borrow current value, marking it as seen.
+    pub fn borrow_and_update(&mut self) -> MappedRwLockReadGuard<'_, T> {
+        let state = self.shared.state.read();
+        self.seen = state.version;
+        RwLockReadGuard::map(state, |state| &state.value)
+    }
+
+    /// Returns [true] if value changed since it was last seen by this handle.
+    pub fn has_changed(&self) -> bool {
+        self.version() != self.seen
+    }
+
+    /// Return current version of value, incremented with every change.
+    pub fn version(&self) -> u64 {
+        self.shared.state.read().version
+    }
+
+    /// Mark current value as seen.
+    pub fn mark_unchanged(&mut self) {
+        self.seen = self.version();
+    }
+
+    /// Wait for value to change since it was last seen by this handle, marking it as seen.
+    pub async fn changed(&mut self) {
+        loop {
+            let notified = self.shared.notifier.notified();
+            let version = self.version();
+            if version != self.seen {
+                self.seen = version;
+                return;
+            }
+            notified.await;
+        }
+    }
+}
+
+impl<T> Watch<T>
+where
+    T: Clone,
+{
+    /// Convert into stream of values, yielding each time value changes.
+    ///
+    /// Changes happening faster than stream is polled are coalesced, only latest value is yielded.
+    pub fn updates(self) -> impl Stream<Item = T> {
+        stream::unfold(self, |mut watch| async move {
+            watch.changed().await;
+            let value = watch.borrow_and_update().clone();
+            Some((value, watch))
+        })
+    }
+}
+
+impl<T> Clone for Watch<T> {
+    fn clone(&self) -> Self {
+        Watch {
+            shared: self.shared.clone(),
+            seen: self.seen,
+        }
+    }
+}
+
+impl<T> Default for Watch<T>
+where
+    T: Default,
+{
+    fn default() -> Self {
+        Watch::new(T::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use futures::{FutureExt, StreamExt};
+
+    use super::Watch;
+    use crate::{
+        spawn,
+        test::{dtest, dtest_configure},
+        time::sleep,
+    };
+
+    dtest_configure!();
+
+    #[dtest]
+    async fn test_watch() {
+        let mut watch = Watch::new(0);
+        assert!(!watch.has_changed());
+        assert!(watch.changed().now_or_never().is_none());
+
+        let mut watch_clone = watch.clone();
+        let join_handle = spawn(async move {
+            let mut values = vec![];
+            while values.len() < 3 {
+                watch_clone.changed().await;
+                values.push(*watch_clone.borrow());
+            }
+            values
+        });
+        for i in 1..=3 {
+            sleep(Duration::from_millis(10)).await;
+            watch.send(i);
+        }
+        assert_eq!(vec![1, 2, 3], join_handle.await.unwrap());
+
+        assert!(watch.has_changed());
+        assert_eq!(3, watch.version());
+        watch.changed().await;
+        assert!(!watch.has_changed());
+        watch.send_modify(|value| *value += 1);
+        assert!(watch.has_changed());
+        assert_eq!(4, *watch.borrow_and_update());
+        assert!(!watch.has_changed());
+    }
+
+    #[dtest]
+    async fn test_watch_updates() {
+        let watch = Watch::new(String::new());
+        let updates = watch.clone().updates();
+        spawn(async move {
+            for value in ["a", "b", "c"] {
+                sleep(Duration::from_millis(10)).await;
+                watch.send(value.to_string());
+            }
+        });
+        assert_eq!(
+            vec!["a", "b", "c"],
+            updates.take(3).collect::<Vec<_>>().await
+        );
+    }
+}